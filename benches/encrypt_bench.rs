@@ -0,0 +1,35 @@
+use std::hint::black_box;
+
+use caesar_cipher_enc_dec::caesar_cipher::{encrypt, encrypt_bytes_in_place};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+const SIZE: usize = 10 * 1024;
+
+fn repeat_to_size(sample: &str) -> String {
+    sample.chars().cycle().take(SIZE).collect()
+}
+
+fn encrypt_bench(c: &mut Criterion) {
+    let ascii = repeat_to_size("The quick brown fox jumps over the lazy dog. ");
+    let unicode = repeat_to_size("Grüße aus 東京, café と naïve! ");
+
+    c.bench_function("encrypt 10k ascii", |b| {
+        b.iter(|| encrypt(black_box(&ascii), black_box(3)))
+    });
+    c.bench_function("encrypt 10k mixed unicode", |b| {
+        b.iter(|| encrypt(black_box(&unicode), black_box(3)))
+    });
+    c.bench_function("encrypt_bytes_in_place 10k ascii", |b| {
+        b.iter_batched(
+            || ascii.clone().into_bytes(),
+            |mut buf| {
+                encrypt_bytes_in_place(black_box(&mut buf), black_box(3));
+                buf
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, encrypt_bench);
+criterion_main!(benches);
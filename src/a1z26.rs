@@ -0,0 +1,45 @@
+//! # a1z26
+//!
+//! the A1Z26 cipher: every letter becomes its position in the alphabet (A = 1 ... Z = 26).
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::caesar_cipher::CipherError;
+
+/// # encode
+///
+/// space-separated letter positions. letters are case-insensitive and
+/// everything else (spaces, digits, punctuation) is skipped.
+///
+/// ```
+/// use caesar_cipher_enc_dec::a1z26::encode;
+/// assert_eq!(encode("hello"), "8 5 12 12 15");
+/// ```
+#[must_use]
+pub fn encode(text: &str) -> String {
+    text.chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| (c.to_ascii_uppercase() as u8 - b'A' + 1).to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// # decode
+///
+/// turn whitespace-separated numbers back into uppercase letters.
+/// anything that is not a number from 1 to 26 is an error.
+pub fn decode(text: &str) -> Result<String, CipherError> {
+    text.split_whitespace()
+        .map(|token| match token.parse::<u8>() {
+            Ok(n @ 1..=26) if token.bytes().all(|b| b.is_ascii_digit()) => {
+                Ok((b'A' + n - 1) as char)
+            }
+            _ => Err(CipherError::InvalidEncoding(format!(
+                "'{}' is not a number from 1 to 26",
+                token
+            ))),
+        })
+        .collect()
+}
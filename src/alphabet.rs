@@ -0,0 +1,86 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::caesar_cipher::CipherError;
+
+/// # Alphabet
+///
+/// an ordered set of letters that shifts wrap around in.
+///
+/// ```
+/// use caesar_cipher_enc_dec::alphabet::Alphabet;
+/// let upper = Alphabet::ascii_upper();
+/// assert_eq!(upper.index_of('C'), Some(2));
+/// assert_eq!(upper.shift('Z', 1), 'A');
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+}
+
+impl Alphabet {
+    pub fn ascii_upper() -> Self {
+        Alphabet {
+            chars: ('A'..='Z').collect(),
+        }
+    }
+    pub fn ascii_lower() -> Self {
+        Alphabet {
+            chars: ('a'..='z').collect(),
+        }
+    }
+    /// an alphabet of `chars` in the given order. a character listed twice
+    /// would make shifting impossible to undo, so that is an error.
+    pub fn custom(chars: &[char]) -> Result<Self, CipherError> {
+        for (i, c) in chars.iter().enumerate() {
+            if chars[..i].contains(c) {
+                return Err(CipherError::InvalidEncoding(format!(
+                    "'{}' appears more than once in the alphabet",
+                    c
+                )));
+            }
+        }
+        Ok(Alphabet {
+            chars: chars.to_vec(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        self.chars.iter().position(|&x| x == c)
+    }
+
+    /// move `c` by `by` places, wrapping around.
+    /// characters outside the alphabet are returned unchanged.
+    pub fn shift(&self, c: char, by: i16) -> char {
+        match self.index_of(c) {
+            Some(index) => {
+                let len = self.chars.len() as i64;
+                self.chars[(index as i64 + by as i64).rem_euclid(len) as usize]
+            }
+            None => c,
+        }
+    }
+}
+
+/// # encrypt_with_alphabets
+///
+/// shift every character within the first alphabet that contains it.
+/// with `ascii_upper` and `ascii_lower` this is the same as `encrypt`.
+#[must_use]
+pub fn encrypt_with_alphabets(text: &str, shift: i16, alphabets: &[Alphabet]) -> String {
+    text.chars()
+        .map(|c| {
+            alphabets
+                .iter()
+                .find(|alphabet| alphabet.index_of(c).is_some())
+                .map_or(c, |alphabet| alphabet.shift(c, shift))
+        })
+        .collect()
+}
@@ -1,8 +1,20 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::config::DEFAULT_SHIFT;
+
 /// # caesar_encrypt
 ///
 /// can easily use caesar_encrypt and decrypt.
 /// set text and shift number
 ///
+/// lowercase letters are shifted as well and stay lowercase.
+/// before 0.7 only A-Z were shifted and lowercase letters passed through.
+///
 /// # usage
 /// ```
 /// use caesar_cipher_enc_dec::caesar_cipher::{encrypt, decrypt};
@@ -19,18 +31,1635 @@
 /// use caesar_cipher_enc_dec::caesar_cipher::encrypt;
 /// let text = "I LOVE YOU";
 /// for i in 0..26{
-///     encrypt(&text, i);
+///     println!("{}", encrypt(&text, i));
 /// }
 /// ```
-
+#[must_use]
 pub fn encrypt(text: &str, shift: i16) -> String {
-    let a_code = 'A' as i16;
-
-    let is_az = |c| 'A' <= c && c <= 'Z';
-    let conv = |c| (((c - a_code + shift + 26) % 26 + a_code) as u8) as char;
-    let enc = |c| if is_az(c) { conv(c as i16) } else { c };
-    return text.chars().map(|c| enc(c)).collect();
+    transform(text, shift, Direction::Forward)
 }
+#[must_use]
 pub fn decrypt(text: &str, shift: i16) -> String {
-    return encrypt(text, -shift);
+    transform(text, shift, Direction::Backward)
+}
+
+/// # encrypt_default
+///
+/// encrypt with `DEFAULT_SHIFT`, the shift the CLI uses when none is given.
+/// unlike the CLI, this ignores `CAESAR_DEFAULT_SHIFT`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{encrypt, encrypt_default};
+/// use caesar_cipher_enc_dec::config::DEFAULT_SHIFT;
+/// assert_eq!(encrypt_default("Hello"), encrypt("Hello", DEFAULT_SHIFT));
+/// ```
+#[must_use]
+pub fn encrypt_default(text: &str) -> String {
+    encrypt(text, DEFAULT_SHIFT)
+}
+
+/// which way `transform` moves the letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// shift forward, i.e. encrypt.
+    Forward,
+    /// shift backward, i.e. decrypt.
+    Backward,
+}
+
+/// # transform
+///
+/// the single entry point behind `encrypt` and `decrypt`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{transform, Direction};
+/// assert_eq!(transform("Hello", 3, Direction::Forward), "Khoor");
+/// assert_eq!(transform("Khoor", 3, Direction::Backward), "Hello");
+/// ```
+#[must_use]
+pub fn transform(text: &str, shift: i16, dir: Direction) -> String {
+    let shift = match dir {
+        Direction::Forward => shift.rem_euclid(26),
+        Direction::Backward => (26 - shift.rem_euclid(26)) % 26,
+    };
+    text.chars().map(|c| shift_char(c, shift)).collect()
+}
+
+/// # shift_char
+///
+/// shift a single character: A-Z and a-z move by `shift` (wrapping, case kept),
+/// everything else is returned unchanged. `encrypt` applies this to every char.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::shift_char;
+/// assert_eq!(shift_char('Z', 1), 'A');
+/// assert_eq!(shift_char('a', -1), 'z');
+/// assert_eq!(shift_char('5', 3), '5');
+/// ```
+#[must_use]
+pub fn shift_char(c: char, shift: i16) -> char {
+    let shift = shift.rem_euclid(26) as u8;
+    let conv = |base: u8| ((c as u8 - base + shift) % 26 + base) as char;
+    match c {
+        'A'..='Z' => conv(b'A'),
+        'a'..='z' => conv(b'a'),
+        _ => c,
+    }
+}
+
+/// # encrypt_with_stats
+///
+/// like `encrypt`, but also returns how many letters were shifted.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_with_stats;
+/// assert_eq!(encrypt_with_stats("ab!", 1), ("bc!".to_string(), 2));
+/// ```
+#[must_use]
+pub fn encrypt_with_stats(text: &str, shift: i16) -> (String, usize) {
+    let mut letters = 0;
+    let enc_text = text
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                letters += 1;
+            }
+            shift_char(c, shift)
+        })
+        .collect();
+    (enc_text, letters)
+}
+
+/// result of `encrypt_detailed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptResult {
+    pub ciphertext: String,
+    /// the shift actually applied, in 0..=25.
+    pub effective_shift: i16,
+    pub letters_shifted: usize,
+}
+
+/// # encrypt_detailed
+///
+/// encrypt and return the ciphertext together with its metadata.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{encrypt_detailed, EncryptResult};
+/// assert_eq!(
+///     encrypt_detailed("Hi!", -1),
+///     EncryptResult {
+///         ciphertext: "Gh!".to_string(),
+///         effective_shift: 25,
+///         letters_shifted: 2,
+///     }
+/// );
+/// ```
+#[must_use]
+pub fn encrypt_detailed(text: &str, shift: i16) -> EncryptResult {
+    let (ciphertext, letters_shifted) = encrypt_with_stats(text, shift);
+    EncryptResult {
+        ciphertext,
+        effective_shift: shift.rem_euclid(26),
+        letters_shifted,
+    }
+}
+
+/// # encrypt_unicode_normalize
+///
+/// like `encrypt`, but accented Latin letters are shifted by their base letter
+/// and keep their accent: the text is decomposed (NFD), shifted and composed
+/// again (NFC). "é" becomes "f" with a combining acute accent, since there is
+/// no precomposed form. other scripts still pass through.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_unicode_normalize;
+/// assert_eq!(encrypt_unicode_normalize("àé", 4), "èí");
+/// ```
+#[cfg(feature = "unicode-normalization")]
+#[must_use]
+pub fn encrypt_unicode_normalize(text: &str, shift: i16) -> String {
+    use unicode_normalization::UnicodeNormalization;
+    text.nfd().map(|c| shift_char(c, shift)).nfc().collect()
+}
+
+/// # encrypt_filtered
+///
+/// like `encrypt`, but only uppercase letters are shifted when `upper` is set
+/// and only lowercase letters when `lower` is set.
+/// `encrypt_filtered(text, shift, true, true)` is the same as `encrypt`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_filtered;
+/// assert_eq!(encrypt_filtered("Hello World", 3, true, false), "Kello Zorld");
+/// ```
+#[must_use]
+pub fn encrypt_filtered(text: &str, shift: i16, upper: bool, lower: bool) -> String {
+    text.chars()
+        .map(|c| match c {
+            'A'..='Z' if upper => shift_char(c, shift),
+            'a'..='z' if lower => shift_char(c, shift),
+            _ => c,
+        })
+        .collect()
+}
+
+/// # CipherBuilder
+///
+/// one entry point for the encrypt options.
+/// the defaults (apart from the shift, which starts at `DEFAULT_SHIFT`)
+/// behave exactly like `encrypt`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::CipherBuilder;
+/// let cipher = CipherBuilder::new()
+///     .shift(1)
+///     .preserve_case(false)
+///     .shift_digits(true)
+///     .strip_non_alpha(true);
+/// assert_eq!(cipher.encrypt("Hi there, 2024!"), "IJUIFSF3135");
+/// assert_eq!(cipher.decrypt("IJUIFSF3135"), "HITHERE2024");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CipherBuilder {
+    shift: i16,
+    preserve_case: bool,
+    lowercase: bool,
+    shift_digits: bool,
+    strip_non_alpha: bool,
+    shift_upper: bool,
+    shift_lower: bool,
+}
+
+impl Default for CipherBuilder {
+    fn default() -> Self {
+        CipherBuilder {
+            shift: DEFAULT_SHIFT,
+            preserve_case: true,
+            lowercase: false,
+            shift_digits: false,
+            strip_non_alpha: false,
+            shift_upper: true,
+            shift_lower: true,
+        }
+    }
+}
+
+impl CipherBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn shift(mut self, shift: i16) -> Self {
+        self.shift = shift;
+        self
+    }
+    /// `false` uppercases the text like `encrypt_upper` (lossy).
+    pub fn preserve_case(mut self, preserve_case: bool) -> Self {
+        self.preserve_case = preserve_case;
+        self
+    }
+    /// `true` lowercases the text like `encrypt_lower` (lossy).
+    /// wins over `preserve_case(false)`.
+    pub fn lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+    /// also rotate 0-9 by the shift (mod 10).
+    pub fn shift_digits(mut self, shift_digits: bool) -> Self {
+        self.shift_digits = shift_digits;
+        self
+    }
+    /// drop everything but letters (and digits when `shift_digits` is on).
+    pub fn strip_non_alpha(mut self, strip_non_alpha: bool) -> Self {
+        self.strip_non_alpha = strip_non_alpha;
+        self
+    }
+    /// `false` leaves uppercase letters as they are.
+    pub fn shift_upper(mut self, shift_upper: bool) -> Self {
+        self.shift_upper = shift_upper;
+        self
+    }
+    /// `false` leaves lowercase letters as they are.
+    pub fn shift_lower(mut self, shift_lower: bool) -> Self {
+        self.shift_lower = shift_lower;
+        self
+    }
+    #[must_use]
+    pub fn encrypt(&self, text: &str) -> String {
+        self.apply(text, self.shift as i32)
+    }
+    #[must_use]
+    pub fn decrypt(&self, text: &str) -> String {
+        self.apply(text, -(self.shift as i32))
+    }
+
+    fn apply(&self, text: &str, shift: i32) -> String {
+        let digit_shift = shift.rem_euclid(10) as u8;
+        let shift = shift.rem_euclid(26) as i16;
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            let shift_letter = (c.is_ascii_uppercase() && self.shift_upper)
+                || (c.is_ascii_lowercase() && self.shift_lower);
+            let c = if self.lowercase {
+                c.to_ascii_lowercase()
+            } else if self.preserve_case {
+                c
+            } else {
+                c.to_ascii_uppercase()
+            };
+            match c {
+                'A'..='Z' | 'a'..='z' if shift_letter => result.push(shift_char(c, shift)),
+                'A'..='Z' | 'a'..='z' => result.push(c),
+                '0'..='9' if self.shift_digits => {
+                    result.push(((c as u8 - b'0' + digit_shift) % 10 + b'0') as char)
+                }
+                _ if self.strip_non_alpha => {}
+                _ => result.push(c),
+            }
+        }
+        result
+    }
+}
+
+/// # Encryptor
+///
+/// encrypts line after line into one reused buffer, for long-running
+/// `stdin` loops that should not allocate per line.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::Encryptor;
+/// let mut encryptor = Encryptor::new(3);
+/// assert_eq!(encryptor.feed("Hello"), "Khoor");
+/// assert_eq!(encryptor.feed("World"), "Zruog");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Encryptor {
+    shift: i16,
+    buf: String,
+}
+
+impl Encryptor {
+    pub fn new(shift: i16) -> Self {
+        Encryptor {
+            shift: shift.rem_euclid(26),
+            buf: String::new(),
+        }
+    }
+
+    /// encrypt `line`; the result borrows the buffer until the next call.
+    pub fn feed(&mut self, line: &str) -> &str {
+        self.buf.clear();
+        self.buf
+            .extend(line.chars().map(|c| shift_char(c, self.shift)));
+        &self.buf
+    }
+
+    /// capacity of the internal buffer, which only grows to the longest line fed.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+}
+
+/// # encrypt_bytes_in_place
+///
+/// shift the ASCII letters of `buf` in place. every other byte, including
+/// the bytes of multi-byte UTF-8 characters, is left alone, so valid UTF-8
+/// stays valid.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_bytes_in_place;
+/// let mut buf = *b"Hello!";
+/// encrypt_bytes_in_place(&mut buf, 3);
+/// assert_eq!(&buf, b"Khoor!");
+/// ```
+pub fn encrypt_bytes_in_place(buf: &mut [u8], shift: i16) {
+    let shift = shift.rem_euclid(26) as u8;
+    for byte in buf {
+        match *byte {
+            b'A'..=b'Z' => *byte = (*byte - b'A' + shift) % 26 + b'A',
+            b'a'..=b'z' => *byte = (*byte - b'a' + shift) % 26 + b'a',
+            _ => {}
+        }
+    }
+}
+
+/// # encrypt_array
+///
+/// `encrypt_bytes_in_place` as a `const fn`, copying the first `N` bytes of
+/// `bytes` into an array. this is what `caesar_encrypt_const!` builds on.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_array;
+/// const OUT: [u8; 5] = encrypt_array(b"Hello", 3);
+/// assert_eq!(&OUT, b"Khoor");
+/// ```
+#[must_use]
+pub const fn encrypt_array<const N: usize>(bytes: &[u8], shift: i16) -> [u8; N] {
+    let shift = shift.rem_euclid(26) as u8;
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = match bytes[i] {
+            b @ b'A'..=b'Z' => (b - b'A' + shift) % 26 + b'A',
+            b @ b'a'..=b'z' => (b - b'a' + shift) % 26 + b'a',
+            b => b,
+        };
+        i += 1;
+    }
+    out
+}
+
+/// # caesar_encrypt_const
+///
+/// encrypt a string literal at compile time, so the plaintext never ends up
+/// in the binary. this only hides strings from a casual look, it is no protection.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_encrypt_const;
+/// const S: &str = caesar_encrypt_const!("Hello", 3);
+/// assert_eq!(S, "Khoor");
+/// ```
+#[macro_export]
+macro_rules! caesar_encrypt_const {
+    ($text:expr, $shift:expr) => {{
+        const LEN: usize = $text.len();
+        const BYTES: [u8; LEN] = $crate::caesar_cipher::encrypt_array($text.as_bytes(), $shift);
+        match ::core::str::from_utf8(&BYTES) {
+            Ok(text) => text,
+            Err(_) => panic!("shifting ASCII letters keeps UTF-8 valid"),
+        }
+    }};
+}
+
+/// # encrypt_owned
+///
+/// like `encrypt`, but shifts the letters of `text` in place and returns the
+/// same `String`, so no second buffer is allocated.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_owned;
+/// assert_eq!(encrypt_owned("Hello".to_string(), 3), "Khoor");
+/// ```
+#[must_use]
+pub fn encrypt_owned(text: String, shift: i16) -> String {
+    let mut bytes = text.into_bytes();
+    encrypt_bytes_in_place(&mut bytes, shift);
+    // only ASCII bytes were changed, into ASCII bytes, so this cannot fail
+    String::from_utf8(bytes).expect("shifting ASCII letters keeps UTF-8 valid")
+}
+
+/// # encrypt_to_bytes
+///
+/// like `encrypt`, but returns the UTF-8 bytes of the ciphertext, for callers
+/// that want bytes rather than a `String`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_to_bytes;
+/// assert_eq!(encrypt_to_bytes("Hello", 3), b"Khoor");
+/// ```
+#[must_use]
+pub fn encrypt_to_bytes(text: &str, shift: i16) -> Vec<u8> {
+    let mut bytes = text.as_bytes().to_vec();
+    encrypt_bytes_in_place(&mut bytes, shift);
+    bytes
+}
+
+/// # encrypt_stream_with_progress
+///
+/// encrypt everything `reader` yields into `writer`, chunk by chunk, so large
+/// files never have to fit in memory. `progress` gets the total number of
+/// bytes processed after each chunk.
+/// only ASCII letters are touched, so multi-byte UTF-8 passes through intact
+/// even when a chunk boundary splits a character.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_stream_with_progress;
+/// let mut out = Vec::new();
+/// encrypt_stream_with_progress("Hello".as_bytes(), &mut out, 3, |_| {}).unwrap();
+/// assert_eq!(out, b"Khoor");
+/// ```
+#[cfg(feature = "std")]
+pub fn encrypt_stream_with_progress<R: std::io::Read, W: std::io::Write>(
+    mut reader: R,
+    mut writer: W,
+    shift: i16,
+    mut progress: impl FnMut(u64),
+) -> std::io::Result<()> {
+    let mut buf = [0u8; 8192];
+    let mut total = 0u64;
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        encrypt_bytes_in_place(&mut buf[..len], shift);
+        writer.write_all(&buf[..len])?;
+        total += len as u64;
+        progress(total);
+    }
+    writer.flush()
+}
+
+/// # EncryptWriter
+///
+/// wraps a writer and shifts the ASCII letters of everything written to it
+/// before passing it on. each byte is shifted on its own, so UTF-8 split
+/// across writes passes through intact.
+///
+/// ```
+/// use std::io::Write;
+/// use caesar_cipher_enc_dec::caesar_cipher::EncryptWriter;
+/// let mut writer = EncryptWriter::new(Vec::new(), 3);
+/// write!(writer, "Hello").unwrap();
+/// assert_eq!(writer.into_inner(), b"Khoor");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct EncryptWriter<W: std::io::Write> {
+    inner: W,
+    shift: i16,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> EncryptWriter<W> {
+    pub fn new(inner: W, shift: i16) -> Self {
+        EncryptWriter { inner, shift }
+    }
+
+    /// the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> std::io::Write for EncryptWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut chunk = [0u8; 8192];
+        let len = buf.len().min(chunk.len());
+        chunk[..len].copy_from_slice(&buf[..len]);
+        encrypt_bytes_in_place(&mut chunk[..len], self.shift);
+        self.inner.write(&chunk[..len])
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// # substitute
+///
+/// replace each letter with its entry in `mapping`, where `mapping[0]` is
+/// the replacement for A, `mapping[1]` for B and so on. the case of the input
+/// letter is kept and everything else passes through.
+/// a Caesar shift is the mapping `cipher_table(shift).1`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::substitute;
+/// let mut mapping = ['A'; 26];
+/// for (entry, c) in mapping.iter_mut().zip("QWERTYUIOPASDFGHJKLZXCVBNM".chars()) {
+///     *entry = c;
+/// }
+/// assert_eq!(substitute("Hello!", &mapping), "Itssg!");
+/// ```
+#[must_use]
+pub fn substitute(text: &str, mapping: &[char; 26]) -> String {
+    text.chars()
+        .map(|c| match c {
+            'A'..='Z' => mapping[(c as u8 - b'A') as usize].to_ascii_uppercase(),
+            'a'..='z' => mapping[(c as u8 - b'a') as usize].to_ascii_lowercase(),
+            _ => c,
+        })
+        .collect()
+}
+
+/// first line of the `armor` format.
+pub const ARMOR_BEGIN: &str = "-----BEGIN CAESAR-----";
+/// last line of the `armor` format.
+pub const ARMOR_END: &str = "-----END CAESAR-----";
+
+/// # armor
+///
+/// wrap `ciphertext` in a self-describing block that records the shift:
+///
+/// ```text
+/// -----BEGIN CAESAR-----
+/// shift: 3
+///
+/// Khoor
+/// -----END CAESAR-----
+/// ```
+///
+/// `dearmor` reads it back.
+#[must_use]
+pub fn armor(ciphertext: &str, shift: i16) -> String {
+    format!(
+        "{}\nshift: {}\n\n{}\n{}",
+        ARMOR_BEGIN, shift, ciphertext, ARMOR_END
+    )
+}
+
+/// # dearmor
+///
+/// the shift and ciphertext of an `armor` block. surrounding whitespace is
+/// ignored; a missing marker, shift line or blank line is an error.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{armor, dearmor};
+/// let block = armor("Khoor\nZruog", 3);
+/// assert_eq!(dearmor(&block), Ok((3, "Khoor\nZruog".to_string())));
+/// ```
+pub fn dearmor(s: &str) -> Result<(i16, String), CipherError> {
+    let malformed = |msg: &str| CipherError::InvalidEncoding(format!("armor: {}", msg));
+    let mut lines = s.trim().lines();
+    if lines.next() != Some(ARMOR_BEGIN) {
+        return Err(malformed("missing BEGIN line"));
+    }
+    let shift = lines
+        .next()
+        .and_then(|line| line.strip_prefix("shift:"))
+        .ok_or_else(|| malformed("missing shift line"))?;
+    let shift = shift
+        .trim()
+        .parse()
+        .map_err(|_| CipherError::InvalidShift(format!("'{}' is not a number", shift.trim())))?;
+    if lines.next() != Some("") {
+        return Err(malformed("missing blank line after the header"));
+    }
+    let body: Vec<&str> = lines.collect();
+    match body.split_last() {
+        Some((&ARMOR_END, body)) => Ok((shift, body.join("\n"))),
+        _ => Err(malformed("missing END line")),
+    }
+}
+
+/// # encrypt_except
+///
+/// like `encrypt`, but whitespace-separated words found in `keep` are left
+/// readable. words are compared ignoring ASCII case, and punctuation counts
+/// as part of the word ("World!" does not match "World").
+/// the whitespace itself is kept as it is.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_except;
+/// assert_eq!(encrypt_except("Hello World", 3, &["world"]), "Khoor World");
+/// ```
+#[must_use]
+pub fn encrypt_except(text: &str, shift: i16, keep: &[&str]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let word_start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        result.push_str(&rest[..word_start]);
+        rest = &rest[word_start..];
+
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        if keep.iter().any(|kept| kept.eq_ignore_ascii_case(word)) {
+            result.push_str(word);
+        } else {
+            result.push_str(&encrypt(word, shift));
+        }
+        rest = &rest[word_end..];
+    }
+    result
+}
+
+/// # contains_non_latin_letters
+///
+/// whether `text` has letters outside A-Z and a-z, such as "é" or "日",
+/// which encryption passes through unchanged.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::contains_non_latin_letters;
+/// assert!(!contains_non_latin_letters("Hello, World!"));
+/// assert!(contains_non_latin_letters("café"));
+/// ```
+pub fn contains_non_latin_letters(text: &str) -> bool {
+    text.chars()
+        .any(|c| c.is_alphabetic() && !c.is_ascii_alphabetic())
+}
+
+/// # ensure_ascii
+///
+/// fail with `CipherError::NonAscii` on the first character outside ASCII,
+/// for callers that never expect anything to pass through unshifted.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{ensure_ascii, CipherError};
+/// assert_eq!(ensure_ascii("Hello!"), Ok(()));
+/// assert_eq!(ensure_ascii("café"), Err(CipherError::NonAscii('é')));
+/// ```
+pub fn ensure_ascii(text: &str) -> Result<(), CipherError> {
+    match text.chars().find(|c| !c.is_ascii()) {
+        Some(c) => Err(CipherError::NonAscii(c)),
+        None => Ok(()),
+    }
+}
+
+/// # change_mask
+///
+/// one entry per char of `text`, true where `encrypt(text, shift)` changes it.
+/// shifts that are multiples of 26 change nothing.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::change_mask;
+/// assert_eq!(change_mask("ab!", 1), vec![true, true, false]);
+/// ```
+pub fn change_mask(text: &str, shift: i16) -> Vec<bool> {
+    let shifts = shift.rem_euclid(26) != 0;
+    text.chars()
+        .map(|c| shifts && c.is_ascii_alphabetic())
+        .collect()
+}
+
+/// # unaffected_chars
+///
+/// the characters of `text` that encryption leaves alone (everything except
+/// A-Z and a-z), each listed once in the order they first appear.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::unaffected_chars;
+/// assert_eq!(unaffected_chars("Hi, wörld!"), vec![',', ' ', 'ö', '!']);
+/// ```
+pub fn unaffected_chars(text: &str) -> Vec<char> {
+    let mut chars = Vec::new();
+    for c in text.chars().filter(|c| !c.is_ascii_alphabetic()) {
+        if !chars.contains(&c) {
+            chars.push(c);
+        }
+    }
+    chars
+}
+
+/// # encrypt_upper
+///
+/// uppercase the text first, then shift it, like the 0.2.1 `encrypt` did.
+/// this is lossy: the original case cannot be recovered by `decrypt`.
+/// only ASCII letters are uppercased, so 'ß' stays 'ß' instead of becoming "SS".
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_upper;
+/// assert_eq!(encrypt_upper("Hello", 3), "KHOOR");
+/// ```
+#[must_use]
+pub fn encrypt_upper(text: &str, shift: i16) -> String {
+    encrypt(&text.to_ascii_uppercase(), shift)
+}
+
+/// # encrypt_lower
+///
+/// lowercase the text first, then shift it. handy to normalize text before
+/// frequency analysis. like `encrypt_upper` this is lossy.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_lower;
+/// assert_eq!(encrypt_lower("Hello", 3), "khoor");
+/// ```
+#[must_use]
+pub fn encrypt_lower(text: &str, shift: i16) -> String {
+    encrypt(&text.to_ascii_lowercase(), shift)
+}
+
+/// # encrypt_prefix
+///
+/// shift only the first `n` characters (not bytes) of `text` and keep the
+/// rest as it is. an `n` past the end of the text shifts all of it.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_prefix;
+/// assert_eq!(encrypt_prefix("Hello World", 3, 5), "Khoor World");
+/// ```
+#[must_use]
+pub fn encrypt_prefix(text: &str, shift: i16, n: usize) -> String {
+    let split = text.char_indices().nth(n).map_or(text.len(), |(i, _)| i);
+    let (head, tail) = text.split_at(split);
+    encrypt(head, shift) + tail
+}
+
+/// # encrypt_stripped
+///
+/// shift the letters and drop everything else, as classic exercises present ciphertext.
+/// this is lossy: `decrypt` will not restore spaces, punctuation or other characters.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_stripped;
+/// assert_eq!(encrypt_stripped("Hi, there!", 1), "Ijuifsf");
+/// ```
+#[must_use]
+pub fn encrypt_stripped(text: &str, shift: i16) -> String {
+    let letters: String = text.chars().filter(char::is_ascii_alphabetic).collect();
+    encrypt(&letters, shift)
+}
+
+/// # group_letters
+///
+/// insert a space every `group_size` characters, e.g. "IJUIFSF" -> "IJUIF SF".
+/// meant for text already run through `encrypt_stripped`.
+/// a `group_size` of 0 returns the text unchanged.
+#[must_use]
+pub fn group_letters(text: &str, group_size: usize) -> String {
+    if group_size == 0 {
+        return text.to_string();
+    }
+    let mut grouped = String::with_capacity(text.len() + text.len() / group_size);
+    for (i, c) in text.chars().enumerate() {
+        if i > 0 && i % group_size == 0 {
+            grouped.push(' ');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// # normalize_shift
+///
+/// the equivalent shift in 0..=25, e.g. 29 -> 3 and -1 -> 25.
+pub fn normalize_shift(shift: i16) -> i16 {
+    shift.rem_euclid(26)
+}
+
+/// # wrap_text
+///
+/// insert a newline every `cols` characters; existing newlines start a new line.
+/// a `cols` of 0 disables wrapping.
+#[must_use]
+pub fn wrap_text(text: &str, cols: usize) -> String {
+    if cols == 0 {
+        return text.to_string();
+    }
+    let mut wrapped = String::with_capacity(text.len() + text.len() / cols);
+    let mut col = 0;
+    for c in text.chars() {
+        if c == '\n' {
+            col = 0;
+        } else {
+            if col == cols {
+                wrapped.push('\n');
+                col = 0;
+            }
+            col += 1;
+        }
+        wrapped.push(c);
+    }
+    wrapped
+}
+
+/// # net_shift
+///
+/// the single shift (0..=25) equivalent to applying every shift in turn.
+/// decrypt with it to undo a chain of encryptions.
+pub fn net_shift(shifts: &[i16]) -> i16 {
+    shifts
+        .iter()
+        .fold(0, |total, shift| (total + shift.rem_euclid(26)) % 26)
+}
+
+/// # encrypt_twice
+///
+/// encrypt with `s1`, then with `s2`. two Caesar shifts make one Caesar shift,
+/// so this is the same as `encrypt(text, s1 + s2)` and is done in one pass.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{encrypt, encrypt_twice};
+/// assert_eq!(encrypt_twice("Hello", 3, 5), encrypt(&encrypt("Hello", 3), 5));
+/// ```
+#[must_use]
+pub fn encrypt_twice(text: &str, s1: i16, s2: i16) -> String {
+    encrypt(text, net_shift(&[s1, s2]))
+}
+
+/// errors returned by the validating functions and the cli.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CipherError {
+    EmptyText,
+    InvalidShift(String),
+    InvalidEncoding(String),
+    /// reading or writing a file failed; the message names the path.
+    Io(String),
+    /// the text has `len` characters, more than the `max` allowed.
+    TooLong {
+        len: usize,
+        max: usize,
+    },
+    /// the text has a character outside ASCII, which strict mode refuses.
+    NonAscii(char),
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::EmptyText => write!(f, "text is empty"),
+            CipherError::InvalidShift(msg) => write!(f, "invalid shift: {}", msg),
+            CipherError::InvalidEncoding(msg) => write!(f, "invalid encoding: {}", msg),
+            CipherError::Io(msg) => write!(f, "i/o error: {}", msg),
+            CipherError::TooLong { len, max } => {
+                write!(f, "text is too long: {} characters, limit is {}", len, max)
+            }
+            CipherError::NonAscii(c) => write!(f, "non-ASCII character {:?} in the text", c),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CipherError {}
+
+/// keeps only the message; map the error by hand where the path matters.
+#[cfg(feature = "std")]
+impl From<std::io::Error> for CipherError {
+    fn from(err: std::io::Error) -> Self {
+        CipherError::Io(err.to_string())
+    }
+}
+
+/// # encrypt_safe
+///
+/// like `encrypt`, but rejects empty text and shifts outside -25..=25.
+pub fn encrypt_safe(text: &str, shift: i16) -> Result<String, CipherError> {
+    validate(text, shift)?;
+    Ok(encrypt(text, shift))
+}
+pub fn decrypt_safe(text: &str, shift: i16) -> Result<String, CipherError> {
+    validate(text, shift)?;
+    Ok(decrypt(text, shift))
+}
+
+/// # encrypt_safe_bounded
+///
+/// like `encrypt_safe`, but also rejects text longer than `max_len` characters
+/// before encrypting anything.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{encrypt_safe_bounded, CipherError};
+/// assert_eq!(encrypt_safe_bounded("abc", 3, 3), Ok("def".to_string()));
+/// assert_eq!(
+///     encrypt_safe_bounded("abcd", 3, 3),
+///     Err(CipherError::TooLong { len: 4, max: 3 })
+/// );
+/// ```
+pub fn encrypt_safe_bounded(text: &str, shift: i16, max_len: usize) -> Result<String, CipherError> {
+    validate(text, shift)?;
+    let len = text.chars().count();
+    if len > max_len {
+        return Err(CipherError::TooLong { len, max: max_len });
+    }
+    Ok(encrypt(text, shift))
+}
+
+fn validate(text: &str, shift: i16) -> Result<(), CipherError> {
+    if text.is_empty() {
+        return Err(CipherError::EmptyText);
+    }
+    if !(-25..=25).contains(&shift) {
+        return Err(CipherError::InvalidShift(format!(
+            "{} is outside -25..=25",
+            shift
+        )));
+    }
+    Ok(())
+}
+
+/// # Cipher
+///
+/// common interface for the cipher algorithms, so they can be used
+/// through a `Box<dyn Cipher>`.
+pub trait Cipher {
+    fn encrypt(&self, text: &str) -> Result<String, CipherError>;
+    fn decrypt(&self, text: &str) -> Result<String, CipherError>;
+}
+
+/// # Caesar
+///
+/// the caesar cipher as a `Cipher`, validated like `encrypt_safe`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{Caesar, Cipher};
+/// let cipher: Box<dyn Cipher> = Box::new(Caesar { shift: 3 });
+/// assert_eq!(cipher.encrypt("Hello").unwrap(), "Khoor");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caesar {
+    pub shift: i16,
+}
+
+impl Cipher for Caesar {
+    fn encrypt(&self, text: &str) -> Result<String, CipherError> {
+        encrypt_safe(text, self.shift)
+    }
+    fn decrypt(&self, text: &str) -> Result<String, CipherError> {
+        decrypt_safe(text, self.shift)
+    }
+}
+
+/// # shift_from_letter
+///
+/// convert a key letter to its shift (A = 0, B = 1, ... Z = 25).
+/// lowercase letters are accepted too.
+pub fn shift_from_letter(c: char) -> Result<i16, CipherError> {
+    if !c.is_ascii_alphabetic() {
+        return Err(CipherError::InvalidShift(format!(
+            "'{}' is not a letter from A to Z",
+            c
+        )));
+    }
+    Ok((c.to_ascii_uppercase() as u8 - b'A') as i16)
+}
+
+/// # shift_from_passphrase
+///
+/// derive a shift from a passphrase: the sum of its letter indices
+/// (A = 0, B = 1, ...) mod 26. case is ignored and non-letters are skipped,
+/// so a passphrase without letters gives 0.
+/// this is a convenience, not key derivation.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::shift_from_passphrase;
+/// assert_eq!(shift_from_passphrase("Bad!"), 4);
+/// ```
+pub fn shift_from_passphrase(pass: &str) -> i16 {
+    pass.chars()
+        .filter(char::is_ascii_alphabetic)
+        .fold(0, |sum, c| {
+            (sum + (c.to_ascii_uppercase() as u8 - b'A') as i16) % 26
+        })
+}
+
+/// # safe_matches_unsafe
+///
+/// check that `encrypt_safe` agrees with `encrypt`.
+/// empty text counts as agreement because there is nothing to disagree on,
+/// a rejected shift does not because `encrypt` accepts every shift.
+pub fn safe_matches_unsafe(text: &str, shift: i16) -> bool {
+    match encrypt_safe(text, shift) {
+        Ok(enc_text) => enc_text == encrypt(text, shift),
+        Err(CipherError::EmptyText) => encrypt(text, shift).is_empty(),
+        Err(_) => false,
+    }
+}
+
+/// relative letter frequencies of English text (A to Z, in percent).
+const ENGLISH_FREQUENCIES: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// short, very frequent English words used to settle short texts.
+const COMMON_WORDS: [&str; 30] = [
+    "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on",
+    "with", "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we", "is",
+    "are", "was",
+];
+
+fn common_word_count(text: &str) -> usize {
+    text.split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| COMMON_WORDS.iter().any(|w| w.eq_ignore_ascii_case(word)))
+        .count()
+}
+
+fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for c in text.chars().filter(char::is_ascii_alphabetic) {
+        counts[(c.to_ascii_uppercase() as u8 - b'A') as usize] += 1;
+    }
+    counts
+}
+
+/// # letter_frequencies
+///
+/// share of each letter A to Z among the letters of `text` (case-insensitive),
+/// summing to 1.0. text without letters gives all zeros.
+pub fn letter_frequencies(text: &str) -> [f64; 26] {
+    let counts = letter_counts(text);
+    let total: usize = counts.iter().sum();
+    let mut freqs = [0.0; 26];
+    if total > 0 {
+        for (freq, count) in freqs.iter_mut().zip(counts) {
+            *freq = count as f64 / total as f64;
+        }
+    }
+    freqs
+}
+
+/// letter statistics of a text, as computed by `frequency_stats`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyStats {
+    /// how often each letter A to Z occurs, case-insensitive. every letter is present.
+    pub counts: BTreeMap<char, usize>,
+    /// the index of coincidence, see `index_of_coincidence`.
+    pub ioc: f64,
+    pub total_letters: usize,
+}
+
+/// # frequency_stats
+///
+/// letter counts, index of coincidence and letter total of `text` in one go.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::frequency_stats;
+/// let stats = frequency_stats("Hello!");
+/// assert_eq!(stats.counts[&'L'], 2);
+/// assert_eq!(stats.total_letters, 5);
+/// ```
+pub fn frequency_stats(text: &str) -> FrequencyStats {
+    let counts = letter_counts(text);
+    FrequencyStats {
+        counts: ('A'..='Z').zip(counts).collect(),
+        ioc: coincidence_of_counts(&counts),
+        total_letters: counts.iter().sum(),
+    }
+}
+
+/// # frequency_chart
+///
+/// one "E |#####" bar per letter, scaled so the most frequent letter
+/// gets `width` marks. all-zero input gives empty bars.
+pub fn frequency_chart(freqs: &[f64; 26], width: usize) -> String {
+    let max = freqs.iter().copied().fold(0.0, f64::max);
+    (b'A'..=b'Z')
+        .zip(freqs)
+        .map(|(letter, &freq)| {
+            let len = if max > 0.0 {
+                (freq / max * width as f64 + 0.5) as usize
+            } else {
+                0
+            };
+            format!("{} |{}\n", letter as char, "#".repeat(len))
+        })
+        .collect()
+}
+
+/// # index_of_coincidence
+///
+/// chance that two letters picked from `text` (A to Z, case-insensitive)
+/// are the same. English is around 0.0667, uniformly random text around 0.0385.
+/// fewer than 2 letters gives 0.0.
+pub fn index_of_coincidence(text: &str) -> f64 {
+    coincidence_of_counts(&letter_counts(text))
+}
+
+/// # is_probably_english
+///
+/// a quick check whether `text` reads like English: its index of coincidence
+/// is above 0.06 and its most common letter is E, T, A or O.
+/// the index of coincidence does not change under a Caesar shift, so on its own
+/// it only says the text is monoalphabetic; the letter check does the rest.
+/// short texts give unreliable answers.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::is_probably_english;
+/// assert!(is_probably_english("the eagle has landed at the east gate"));
+/// assert!(!is_probably_english("wkh hdjoh kdv odqghg dw wkh hdvw jdwh"));
+/// ```
+pub fn is_probably_english(text: &str) -> bool {
+    let counts = letter_counts(text);
+    let most_common = (0..26).rev().max_by_key(|&i| counts[i]).unwrap_or(0);
+    coincidence_of_counts(&counts) > 0.06 && [4, 19, 0, 14].contains(&most_common)
+}
+
+/// # likely_key_lengths
+///
+/// rank the key lengths 1..=`max_len` of a Vigenère ciphertext. for each length
+/// the letters are split into that many columns, and the average index of
+/// coincidence of the columns is compared with English (0.0667).
+/// the closest come first; multiples of the real length score well too.
+pub fn likely_key_lengths(text: &str, max_len: usize) -> Vec<(usize, f64)> {
+    let letters: Vec<u8> = text
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase() as u8 - b'A')
+        .collect();
+    let mut lengths: Vec<(usize, f64)> = (1..=max_len)
+        .map(|len| {
+            let mut columns = vec![[0usize; 26]; len];
+            for (i, &letter) in letters.iter().enumerate() {
+                columns[i % len][letter as usize] += 1;
+            }
+            let total: f64 = columns.iter().map(coincidence_of_counts).sum();
+            (len, total / len as f64)
+        })
+        .collect();
+    lengths.sort_by(|a, b| {
+        let distance = |ic: f64| (ic - ENGLISH_IC).abs();
+        distance(a.1).total_cmp(&distance(b.1))
+    });
+    lengths
+}
+
+/// index of coincidence of English text.
+const ENGLISH_IC: f64 = 0.0667;
+
+fn coincidence_of_counts(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+        return 0.0;
+    }
+    let pairs: usize = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+/// chi-squared score of `counts` against English after undoing `shift`.
+fn chi_squared_shifted(counts: &[usize; 26], shift: i16) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let shift = shift.rem_euclid(26) as usize;
+    (0..26)
+        .map(|i| {
+            let observed = counts[(i + shift) % 26] as f64;
+            let expected = ENGLISH_FREQUENCIES[i] / 100.0 * total as f64;
+            (observed - expected) * (observed - expected) / expected
+        })
+        .sum()
+}
+
+/// # chi_squared
+///
+/// score how far the letter distribution of `text` is from English.
+/// lower is more English-like. text without letters scores 0.0.
+pub fn chi_squared(text: &str) -> f64 {
+    chi_squared_shifted(&letter_counts(text), 0)
+}
+
+/// # brute_force
+///
+/// decrypt `text` with every shift from 0 to 25.
+pub fn brute_force(text: &str) -> Vec<(i16, String)> {
+    (0..26).map(|shift| (shift, decrypt(text, shift))).collect()
+}
+
+/// # brute_force_parallel
+///
+/// `brute_force` with the 26 decryptions spread over the rayon thread pool.
+/// worth it for multi-megabyte input; the result is the same, ordered by shift.
+#[cfg(feature = "rayon")]
+pub fn brute_force_parallel(text: &str) -> Vec<(i16, String)> {
+    use rayon::prelude::*;
+    (0..26i16)
+        .into_par_iter()
+        .map(|shift| (shift, decrypt(text, shift)))
+        .collect()
+}
+
+/// # decrypt_all
+///
+/// like `brute_force`, but keyed by shift for random access.
+/// iterating the map still goes from shift 0 to 25.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::decrypt_all;
+/// let all = decrypt_all("Khoor");
+/// assert_eq!(all[&3], "Hello");
+/// ```
+pub fn decrypt_all(text: &str) -> BTreeMap<i16, String> {
+    brute_force(text).into_iter().collect()
+}
+
+struct Candidate {
+    shift: i16,
+    plain_text: String,
+    words: usize,
+    score: f64,
+}
+
+/// every shift of `text`, best first.
+/// more common English words rank higher, ties go to the lowest chi-squared score.
+fn ranked_candidates(text: &str) -> Vec<Candidate> {
+    ranked_candidates_by(text, common_word_count)
+}
+
+/// like `ranked_candidates`, but counting words with `word_score`.
+fn ranked_candidates_by(text: &str, word_score: impl Fn(&str) -> usize) -> Vec<Candidate> {
+    let counts = letter_counts(text);
+    let mut candidates: Vec<Candidate> = brute_force(text)
+        .into_iter()
+        .map(|(shift, plain_text)| Candidate {
+            shift,
+            words: word_score(&plain_text),
+            score: chi_squared_shifted(&counts, shift),
+            plain_text,
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.words.cmp(&a.words).then(a.score.total_cmp(&b.score)));
+    candidates
+}
+
+/// relative chi-squared gap between the two best candidates, in 0.0..=1.0.
+fn confidence(candidates: &[Candidate]) -> f64 {
+    match candidates {
+        [best, second, ..] if second.score > 0.0 => {
+            ((second.score - best.score) / second.score).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+/// # best_shift
+///
+/// guess the shift used to encrypt `text`.
+/// the candidate containing the most common English words wins,
+/// ties are broken by the lowest chi-squared score.
+pub fn best_shift(text: &str) -> i16 {
+    ranked_candidates(text)[0].shift
+}
+
+/// # scored_candidates
+///
+/// every shift of `text` with its decryption and chi-squared score, sorted
+/// by score, lowest (most English-like) first. unlike `best_shift` this
+/// looks at letter frequencies only, so on very short text the first entry
+/// can differ from `best_shift`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{encrypt, scored_candidates};
+/// let candidates = scored_candidates(&encrypt("meet me near the old harbour tonight", 4));
+/// assert_eq!(candidates[0].0, 4);
+/// assert_eq!(candidates[0].1, "meet me near the old harbour tonight");
+/// ```
+pub fn scored_candidates(text: &str) -> Vec<(i16, String, f64)> {
+    let counts = letter_counts(text);
+    let mut candidates: Vec<(i16, String, f64)> = brute_force(text)
+        .into_iter()
+        .map(|(shift, plain_text)| (shift, plain_text, chi_squared_shifted(&counts, shift)))
+        .collect();
+    candidates.sort_by(|a, b| a.2.total_cmp(&b.2));
+    candidates
+}
+
+/// # best_shift_multi
+///
+/// guess the shared shift of several texts encrypted with the same key.
+/// the letter counts of all samples are added up before scoring, which
+/// works where each sample alone is too short to crack.
+/// no samples, or no letters in them, gives 0.
+pub fn best_shift_multi(samples: &[&str]) -> i16 {
+    let mut counts = [0; 26];
+    for sample in samples {
+        for (total, count) in counts.iter_mut().zip(letter_counts(sample)) {
+            *total += count;
+        }
+    }
+    (0..26)
+        .min_by(|&a, &b| {
+            chi_squared_shifted(&counts, a).total_cmp(&chi_squared_shifted(&counts, b))
+        })
+        .unwrap_or(0)
+}
+
+/// # score_by_dictionary
+///
+/// how many whitespace-separated tokens of `text` are in `words`.
+/// tokens are lowercased and stripped of surrounding punctuation first,
+/// so `words` should hold lowercase entries.
+#[cfg(feature = "std")]
+pub fn score_by_dictionary(text: &str, words: &std::collections::HashSet<String>) -> usize {
+    text.split_whitespace()
+        .map(|token| {
+            token
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|token| words.contains(token))
+        .count()
+}
+
+/// # best_shift_with_dictionary
+///
+/// like `best_shift`, but the candidate with the most tokens in `words` wins,
+/// with the chi-squared score as tiebreaker. better than plain chi-squared
+/// on short texts.
+#[cfg(feature = "std")]
+pub fn best_shift_with_dictionary(text: &str, words: &std::collections::HashSet<String>) -> i16 {
+    ranked_candidates_by(text, |plain_text| score_by_dictionary(plain_text, words))[0].shift
+}
+
+/// # crack
+///
+/// detect the shift of `text` and decrypt it in one call.
+/// empty or letter-free text returns `(0, text)`.
+///
+/// # Example
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::{crack, encrypt};
+/// let enc_text = encrypt("Attack at dawn, the enemy is near", 5);
+/// assert_eq!(crack(&enc_text), (5, "Attack at dawn, the enemy is near".to_string()));
+/// ```
+pub fn crack(text: &str) -> (i16, String) {
+    if !text.chars().any(|c| c.is_ascii_alphabetic()) {
+        return (0, text.to_string());
+    }
+    let shift = best_shift(text);
+    (shift, decrypt(text, shift))
+}
+
+/// # crack_with_confidence
+///
+/// like `crack`, plus a confidence in 0.0..=1.0: the relative gap between
+/// the chi-squared scores of the best and second-best candidates.
+/// letter-free text returns `(0, text, 0.0)`.
+pub fn crack_with_confidence(text: &str) -> (i16, String, f64) {
+    if !text.chars().any(|c| c.is_ascii_alphabetic()) {
+        return (0, text.to_string(), 0.0);
+    }
+    let mut candidates = ranked_candidates(text);
+    let confidence = confidence(&candidates);
+    let best = candidates.swap_remove(0);
+    (best.shift, best.plain_text, confidence)
+}
+
+/// # decrypt_best
+///
+/// the validated cousin of `crack`: empty or whitespace-only text is an error.
+pub fn decrypt_best(text: &str) -> Result<(i16, String), CipherError> {
+    if text.trim().is_empty() {
+        return Err(CipherError::EmptyText);
+    }
+    Ok(crack(text))
+}
+
+/// # crack_summary
+///
+/// human-readable report of a crack attempt: the guessed shift,
+/// a confidence between 0 and 1 and the top 3 candidates with their scores.
+pub fn crack_summary(text: &str) -> String {
+    let candidates = ranked_candidates(text);
+    let confidence = confidence(&candidates);
+
+    let mut summary = format!(
+        "Guessed shift: {}\nConfidence: {:.2}\nTop candidates:\n",
+        candidates[0].shift, confidence
+    );
+    for (rank, candidate) in candidates.iter().take(3).enumerate() {
+        summary += &format!(
+            "  {}. shift {:2} (score {:.2}): {}\n",
+            rank + 1,
+            candidate.shift,
+            candidate.score,
+            candidate.plain_text
+        );
+    }
+    if confidence < 0.2 {
+        summary += "Note: the result is ambiguous, check the other candidates.\n";
+    }
+    summary
+}
+
+/// # cheat_sheet
+///
+/// one "A -> D" line per letter showing where encryption sends it.
+pub fn cheat_sheet(shift: i16) -> String {
+    let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    alphabet
+        .chars()
+        .zip(encrypt(alphabet, shift).chars())
+        .map(|(plain, cipher)| format!("{} -> {}\n", plain, cipher))
+        .collect()
+}
+
+/// # side_by_side
+///
+/// one "H→K" line per character of `text`, the original on the left and its
+/// encryption on the right. characters that are not shifted, wide ones
+/// included, show up as pairs of themselves.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::side_by_side;
+/// assert_eq!(side_by_side("a!", 1), "a→b\n!→!\n");
+/// ```
+#[must_use]
+pub fn side_by_side(text: &str, shift: i16) -> String {
+    text.chars()
+        .map(|c| format!("{}→{}\n", c, shift_char(c, shift)))
+        .collect()
+}
+
+/// # cipher_table
+///
+/// the plaintext alphabet and the alphabet shifted by `shift`.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::cipher_table;
+/// let (plain, cipher) = cipher_table(3);
+/// assert_eq!(plain, "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+/// assert_eq!(cipher, "DEFGHIJKLMNOPQRSTUVWXYZABC");
+/// ```
+pub fn cipher_table(shift: i16) -> (String, String) {
+    let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    (alphabet.to_string(), encrypt(alphabet, shift))
+}
+
+/// # full_tabula_recta
+///
+/// all 26 shifted alphabets, one per line, starting with shift 0.
+/// this is the classic Vigenère tableau.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::full_tabula_recta;
+/// let table = full_tabula_recta();
+/// assert_eq!(table.lines().nth(1), Some("BCDEFGHIJKLMNOPQRSTUVWXYZA"));
+/// ```
+pub fn full_tabula_recta() -> String {
+    let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    (0..26)
+        .map(|shift| encrypt(alphabet, shift) + "\n")
+        .collect()
+}
+
+/// # random_shift
+///
+/// a random shift in 1..=25, for when the exact key doesn't matter.
+/// 0 is never picked since it would leave the text unchanged.
+#[cfg(feature = "std")]
+pub fn random_shift() -> i16 {
+    use rand::Rng;
+    rand::thread_rng().gen_range(1..=25)
+}
+
+/// # random_shift_seeded
+///
+/// like `random_shift`, but the same `seed` always gives the same shift
+/// (with the same version of `rand`).
+#[cfg(feature = "std")]
+pub fn random_shift_seeded(seed: u64) -> i16 {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    StdRng::seed_from_u64(seed).gen_range(1..=25)
+}
+
+/// # decrypt_cheat_sheet
+///
+/// the inverse of `cheat_sheet`: one "D -> A" line per ciphertext letter.
+pub fn decrypt_cheat_sheet(shift: i16) -> String {
+    cheat_sheet(-normalize_shift(shift))
+}
+
+/// # shifts_matching
+///
+/// every shift in 0..=25 for which `decrypt(cipher, shift)` equals `target`,
+/// ignoring case.
+pub fn shifts_matching(cipher: &str, target: &str) -> Vec<i16> {
+    brute_force(cipher)
+        .into_iter()
+        .filter(|(_, plain_text)| plain_text.eq_ignore_ascii_case(target))
+        .map(|(shift, _)| shift)
+        .collect()
+}
+
+/// # are_caesar_related
+///
+/// the shift in 0..=25 with `encrypt(a, shift) == b`, if there is one.
+/// every letter pair has to agree on the shift and keep its case,
+/// and everything else has to match exactly.
+/// text without letters is related by shift 0 when both sides are equal.
+///
+/// # Example
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::are_caesar_related;
+/// assert_eq!(are_caesar_related("ABC", "DEF"), Some(3));
+/// assert_eq!(are_caesar_related("ABC", "DEG"), None);
+/// ```
+pub fn are_caesar_related(a: &str, b: &str) -> Option<i16> {
+    if a.chars().count() != b.chars().count() {
+        return None;
+    }
+    let mut shift = None;
+    for (x, y) in a.chars().zip(b.chars()) {
+        let same_case = (x.is_ascii_uppercase() && y.is_ascii_uppercase())
+            || (x.is_ascii_lowercase() && y.is_ascii_lowercase());
+        if !x.is_ascii_alphabetic() {
+            if x != y {
+                return None;
+            }
+        } else if same_case {
+            let pair_shift = (y as i16 - x as i16).rem_euclid(26);
+            if *shift.get_or_insert(pair_shift) != pair_shift {
+                return None;
+            }
+        } else {
+            return None;
+        }
+    }
+    Some(shift.unwrap_or(0))
+}
+
+/// # encrypt_lines
+///
+/// encrypt every line with the same shift, keeping their order.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_lines;
+/// assert_eq!(encrypt_lines(&["ab", "", "c"], 1), ["bc", "", "d"]);
+/// ```
+#[must_use]
+pub fn encrypt_lines(lines: &[&str], shift: i16) -> Vec<String> {
+    lines.iter().map(|line| encrypt(line, shift)).collect()
+}
+
+/// # encrypt_lines_cascading
+///
+/// encrypt line `n` (from 0) with shift `base + step * n`.
+/// line endings are kept exactly as they are.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_lines_cascading;
+/// assert_eq!(encrypt_lines_cascading("aa\naa\n", 1, 2), "bb\ndd\n");
+/// ```
+#[must_use]
+pub fn encrypt_lines_cascading(text: &str, base: i16, step: i16) -> String {
+    text.split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let shift = (base as i64 + step as i64 * index as i64).rem_euclid(26) as i16;
+            encrypt(line, shift)
+        })
+        .collect()
+}
+
+/// # constant_time_eq
+///
+/// compare two strings without returning early, so the time taken does not
+/// reveal where they differ. strings of different length are still scanned
+/// over the longer one before returning false.
+/// caesar is not real cryptography, this only avoids the most obvious timing leak.
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut diff = (a.len() != b.len()) as u8;
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// # encrypt_with_key_sequence
+///
+/// shift the n-th letter by `key[n % key.len()]`, a Vigenère cipher with
+/// numeric keys. non-letters pass through and do not use up a key entry.
+/// an empty key returns the text unchanged.
+///
+/// ```
+/// use caesar_cipher_enc_dec::caesar_cipher::encrypt_with_key_sequence;
+/// assert_eq!(encrypt_with_key_sequence("AA AA", &[1, 2]), "BC BC");
+/// ```
+#[must_use]
+pub fn encrypt_with_key_sequence(text: &str, key: &[i16]) -> String {
+    if key.is_empty() {
+        return text.to_string();
+    }
+    let mut index = 0;
+    text.chars()
+        .map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            let shifted = shift_char(c, key[index % key.len()]);
+            index += 1;
+            shifted
+        })
+        .collect()
 }
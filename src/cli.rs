@@ -0,0 +1,1227 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::a1z26;
+#[cfg(feature = "json")]
+use crate::caesar_cipher::frequency_stats;
+use crate::caesar_cipher::{
+    are_caesar_related, armor, best_shift_with_dictionary, brute_force, change_mask, cipher_table,
+    contains_non_latin_letters, crack, crack_summary, dearmor, decrypt, encrypt,
+    encrypt_lines_cascading, encrypt_prefix, encrypt_stream_with_progress, ensure_ascii,
+    frequency_chart, full_tabula_recta, group_letters, index_of_coincidence, letter_frequencies,
+    normalize_shift, random_shift, random_shift_seeded, shift_from_letter, shift_from_passphrase,
+    side_by_side, substitute, wrap_text, CipherBuilder, CipherError, Direction, ARMOR_BEGIN,
+};
+use crate::config::default_shift_from_env;
+use crate::interactive::run_interactive_mode;
+
+#[derive(Parser)]
+#[command(
+    name = "caesar_cipher_enc_dec",
+    version = crate::VERSION,
+    about = "can easily use caesar cipher"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+/// where the text to process comes from.
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct InputArgs {
+    /// Text to process
+    #[arg(short, long)]
+    pub text: Option<String>,
+    /// Read the text from a file
+    #[arg(short, long)]
+    pub file: Option<String>,
+    /// Read the text from an environment variable, keeping it out of the shell history
+    #[arg(long, value_name = "VARNAME")]
+    pub text_env: Option<String>,
+}
+
+/// how `--file` input is decoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputEncoding {
+    #[default]
+    Utf8,
+    /// every byte is one character (ISO-8859-1)
+    Latin1,
+}
+
+/// how `brute-force --output-format` lays out the candidates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BruteFormat {
+    /// one "Shift N: TEXT" line per shift
+    Lines,
+    /// aligned columns under a header
+    Table,
+    /// "shift,text" rows with a header
+    Csv,
+}
+
+/// options shared by `encrypt` and `decrypt`.
+#[derive(Args)]
+pub struct CipherArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// Encoding of the --file input
+    #[arg(long, value_enum, default_value_t = InputEncoding::Utf8)]
+    pub input_encoding: InputEncoding,
+    /// Number of positions to shift
+    #[arg(short, long, allow_negative_numbers = true, value_parser = parse_shift)]
+    pub shift: Option<i16>,
+    /// Key letter to shift by (A = 0, B = 1, ...)
+    #[arg(long, conflicts_with = "shift")]
+    pub shift_letter: Option<char>,
+    /// Derive the shift from a passphrase (sum of letter indices mod 26)
+    #[arg(long, conflicts_with_all = ["shift", "shift_letter"])]
+    pub passphrase: Option<String>,
+    /// Uppercase the output (the original case is lost)
+    #[arg(long)]
+    pub uppercase: bool,
+    /// Lowercase the output (the original case is lost)
+    #[arg(long, conflicts_with = "uppercase")]
+    pub lowercase: bool,
+    /// Drop everything but letters from the output
+    #[arg(long)]
+    pub strip: bool,
+    /// Only shift uppercase letters
+    #[arg(long, conflicts_with = "lower_only")]
+    pub upper_only: bool,
+    /// Only shift lowercase letters
+    #[arg(long)]
+    pub lower_only: bool,
+    /// Fail instead of passing non-ASCII characters through
+    #[arg(long)]
+    pub strict_ascii: bool,
+    /// Only shift the first N characters and keep the rest as it is
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["uppercase", "lowercase", "strip", "upper_only", "lower_only"]
+    )]
+    pub only_first: Option<usize>,
+    /// Split the output into groups of N characters (default 5)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "5")]
+    pub group: Option<usize>,
+    /// Wrap the output every N characters (0 disables wrapping)
+    #[arg(long, value_name = "N")]
+    pub wrap: Option<usize>,
+    /// Decode the input from hex before processing
+    #[arg(long)]
+    pub hex_in: bool,
+    /// Hex-encode the output
+    #[arg(long)]
+    pub hex_out: bool,
+    /// Decode the input from Base64 before processing
+    #[arg(long, conflicts_with = "hex_in")]
+    pub base64_in: bool,
+    /// Base64-encode the output
+    #[arg(long, conflicts_with = "hex_out")]
+    pub base64_out: bool,
+    /// Write the result to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// Append to --output instead of overwriting it
+    #[arg(long, requires = "output")]
+    pub append: bool,
+    /// Do not print the confirmation after writing --output or warnings
+    #[arg(short, long)]
+    pub quiet: bool,
+    /// Report the effective shift and character counts on stderr
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+impl CipherArgs {
+    fn resolve_shift(&self) -> Result<i16, Box<dyn Error>> {
+        match (self.shift, self.shift_letter, &self.passphrase) {
+            (Some(shift), _, _) => Ok(shift),
+            (None, Some(letter), _) => Ok(shift_from_letter(letter)?),
+            (None, None, Some(pass)) => Ok(shift_from_passphrase(pass)),
+            (None, None, None) => Ok(default_shift_from_env()),
+        }
+    }
+
+    /// how many letters of `text` these options shift, honoring
+    /// `--upper-only`, `--lower-only` and `--only-first`.
+    fn shifted_letters(&self, text: &str) -> usize {
+        text.chars()
+            .take(self.only_first.unwrap_or(usize::MAX))
+            .filter(|c| {
+                (c.is_ascii_uppercase() && !self.lower_only)
+                    || (c.is_ascii_lowercase() && !self.upper_only)
+            })
+            .count()
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Encrypt text
+    Encrypt {
+        #[command(flatten)]
+        args: CipherArgs,
+        /// Only report how many characters would be shifted
+        #[arg(long)]
+        count_only: bool,
+        /// Pick a random shift in 1..=25 and print it to stderr
+        #[arg(long, conflicts_with_all = ["shift", "shift_letter", "passphrase"])]
+        random_shift: bool,
+        /// Seed for --random-shift, so the same seed picks the same shift
+        #[arg(long, requires = "random_shift")]
+        seed: Option<u64>,
+        /// Stream the --file input and show the progress on stderr
+        #[arg(
+            long,
+            requires = "file",
+            conflicts_with_all = [
+                "count_only", "uppercase", "lowercase", "strip", "upper_only", "lower_only",
+                "group", "wrap", "hex_in", "hex_out", "base64_in", "base64_out", "embed_shift",
+                "armor", "only_first", "strict_ascii", "verbose", "append",
+            ]
+        )]
+        progress: bool,
+        /// Put a "#shift:N" line in front of the output for decrypt to pick up
+        #[arg(long)]
+        embed_shift: bool,
+        /// Wrap the output in a BEGIN/END CAESAR block that records the shift
+        #[arg(long, conflicts_with = "embed_shift")]
+        armor: bool,
+    },
+    /// Decrypt text
+    Decrypt {
+        #[command(flatten)]
+        args: CipherArgs,
+        /// Read the shift from the first line of --file and decrypt the rest
+        #[arg(
+            long,
+            requires = "file",
+            conflicts_with_all = ["shift", "shift_letter", "passphrase"]
+        )]
+        shift_from_file: bool,
+    },
+    /// Decrypt with every shift from 0 to 25
+    BruteForce {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Only output the most likely decryption
+        #[arg(long)]
+        best_only: bool,
+        /// Write the output to a file instead of stdout, in the same
+        /// --output-format (or just the best decryption with --best-only)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Print every shift as lines (the default), an aligned table or CSV
+        #[arg(long, value_enum, conflicts_with = "best_only")]
+        output_format: Option<BruteFormat>,
+    },
+    /// Decrypt with every shift in a range
+    Try {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Inclusive range of shifts, e.g. 3:7
+        #[arg(
+            long,
+            value_name = "START:END",
+            value_parser = parse_shift_range,
+            allow_hyphen_values = true
+        )]
+        shift_range: (i16, i16),
+    },
+    /// Encrypt each line of a file with a growing shift
+    Cascade {
+        /// File to encrypt
+        #[arg(short, long)]
+        file: String,
+        /// Shift of the first line (default: $CAESAR_DEFAULT_SHIFT or 3)
+        #[arg(
+            long,
+            allow_negative_numbers = true,
+            value_parser = parse_shift
+        )]
+        base_shift: Option<i16>,
+        /// Shift added for every following line
+        #[arg(long, allow_negative_numbers = true, value_parser = parse_shift, default_value_t = 1)]
+        step: i16,
+    },
+    /// Convert letters to numbers (A = 1 ... Z = 26) and back
+    A1z26 {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Decode numbers back into letters
+        #[arg(short, long)]
+        decode: bool,
+    },
+    /// Show the plain alphabet above the shifted one
+    Table {
+        /// Number of positions to shift (default: $CAESAR_DEFAULT_SHIFT or 3)
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            value_parser = parse_shift
+        )]
+        shift: Option<i16>,
+    },
+    /// Show all 26 shifted alphabets (the tabula recta)
+    FullTable,
+    /// Show how often each letter occurs
+    Analyze {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Draw the frequencies as a bar chart
+        #[arg(long)]
+        chart: bool,
+        /// Print counts, index of coincidence and letter total as JSON
+        #[cfg(feature = "json")]
+        #[arg(long, conflicts_with_all = ["chart", "columns"])]
+        stats_json: bool,
+        /// Lay the letters out in N columns (0 means one column)
+        #[arg(long, value_name = "N", conflicts_with = "chart")]
+        columns: Option<usize>,
+    },
+    /// Encrypt and decrypt from a menu
+    Interactive {
+        /// Append a line per operation (no text) to this file
+        #[arg(long, value_name = "PATH")]
+        history: Option<String>,
+    },
+    /// Detect the shift and decrypt in one step
+    Crack {
+        #[command(flatten)]
+        input: InputArgs,
+        /// Print a report with the top candidates instead of the plain text
+        #[arg(long)]
+        summary: bool,
+        /// Score candidates by the words found in this word list
+        #[arg(long, value_name = "PATH", conflicts_with = "summary")]
+        dictionary: Option<String>,
+    },
+    /// Print the index of coincidence (about 0.0667 for English)
+    Ic {
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Encrypt or decrypt several files into a directory
+    Batch {
+        /// Files to process
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Directory to write the results to
+        #[arg(long, value_name = "DIR")]
+        out_dir: String,
+        /// Number of positions to shift (default: $CAESAR_DEFAULT_SHIFT or 3)
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            value_parser = parse_shift
+        )]
+        shift: Option<i16>,
+        /// Decrypt instead of encrypt
+        #[arg(short, long)]
+        decrypt: bool,
+        /// Insert this before the file extension, e.g. enc for notes.enc.txt
+        #[arg(long)]
+        suffix: Option<String>,
+    },
+    /// Show the text, its encryption and a ^ under every changed character
+    Diff {
+        /// Text to encrypt
+        #[arg(short, long)]
+        text: String,
+        /// Number of positions to shift (default: $CAESAR_DEFAULT_SHIFT or 3)
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            value_parser = parse_shift
+        )]
+        shift: Option<i16>,
+    },
+    /// Show each character next to its encryption, one per line
+    Compare {
+        /// Text to encrypt
+        #[arg(short, long)]
+        text: String,
+        /// Number of positions to shift (default: $CAESAR_DEFAULT_SHIFT or 3)
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            value_parser = parse_shift
+        )]
+        shift: Option<i16>,
+    },
+    /// Find the shift that turns a plaintext into its ciphertext
+    FindShift {
+        /// The known plaintext
+        #[arg(long)]
+        plain: String,
+        /// The ciphertext it was encrypted to
+        #[arg(long)]
+        cipher: String,
+    },
+    /// Print the equivalent shift in 0..=25
+    EffectiveShift {
+        /// Shift to normalize, e.g. 33 or -1
+        #[arg(short, long, allow_negative_numbers = true, value_parser = parse_shift)]
+        shift: i16,
+    },
+    /// Replace letters using a substitution alphabet from a file
+    Substitute {
+        #[command(flatten)]
+        input: InputArgs,
+        /// File with the 26 replacement letters for A to Z
+        #[arg(long, value_name = "PATH")]
+        map_file: String,
+    },
+    /// Encrypt one column of a CSV file
+    Csv {
+        /// CSV file to read
+        #[arg(short, long)]
+        file: String,
+        /// Index of the column to encrypt, starting at 0
+        #[arg(short, long)]
+        column: usize,
+        /// Number of positions to shift (default: $CAESAR_DEFAULT_SHIFT or 3)
+        #[arg(
+            short,
+            long,
+            allow_negative_numbers = true,
+            value_parser = parse_shift
+        )]
+        shift: Option<i16>,
+        /// Encrypt the first row too instead of keeping it as the header
+        #[arg(long)]
+        include_header: bool,
+        /// Write the result to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+impl Commands {
+    /// the subcommand name as typed on the command line.
+    fn name(&self) -> &'static str {
+        match self {
+            Commands::Encrypt { .. } => "encrypt",
+            Commands::Decrypt { .. } => "decrypt",
+            Commands::BruteForce { .. } => "brute-force",
+            Commands::Try { .. } => "try",
+            Commands::Cascade { .. } => "cascade",
+            Commands::A1z26 { .. } => "a1z26",
+            Commands::Table { .. } => "table",
+            Commands::FullTable => "full-table",
+            Commands::Analyze { .. } => "analyze",
+            Commands::Interactive { .. } => "interactive",
+            Commands::Crack { .. } => "crack",
+            Commands::Ic { .. } => "ic",
+            Commands::Batch { .. } => "batch",
+            Commands::Diff { .. } => "diff",
+            Commands::Compare { .. } => "compare",
+            Commands::FindShift { .. } => "find-shift",
+            Commands::EffectiveShift { .. } => "effective-shift",
+            Commands::Substitute { .. } => "substitute",
+            Commands::Csv { .. } => "csv",
+        }
+    }
+}
+
+pub fn run_cli(command: Commands) -> Result<(), Box<dyn Error>> {
+    log::debug!("running the {} command", command.name());
+    match command {
+        Commands::Encrypt {
+            args,
+            count_only,
+            random_shift: use_random_shift,
+            seed,
+            progress,
+            embed_shift,
+            armor: use_armor,
+        } => {
+            let shift = if use_random_shift {
+                let shift = match seed {
+                    Some(seed) => random_shift_seeded(seed),
+                    None => random_shift(),
+                };
+                eprintln!("Shift: {}", shift);
+                shift
+            } else {
+                args.resolve_shift()?
+            };
+            if count_only {
+                let text = get_cipher_input(&args)?;
+                println!(
+                    "{} of {} characters would be shifted",
+                    args.shifted_letters(&text),
+                    text.chars().count()
+                );
+            } else if progress {
+                run_stream_with_progress(&args, shift)?;
+            } else {
+                let framing = if use_armor {
+                    Framing::Armor
+                } else if embed_shift {
+                    Framing::EmbedShift
+                } else {
+                    Framing::Plain
+                };
+                run_cipher(
+                    &args,
+                    &get_cipher_input(&args)?,
+                    shift,
+                    Direction::Forward,
+                    framing,
+                )?;
+            }
+        }
+        Commands::Decrypt {
+            args,
+            shift_from_file,
+        } => {
+            let text = get_cipher_input(&args)?;
+            let explicit =
+                args.shift.is_some() || args.shift_letter.is_some() || args.passphrase.is_some();
+            let (embedded, body) = if shift_from_file {
+                let (shift, body) = split_shift_line(&text)?;
+                (Some(shift), body)
+            } else if text.trim_start().starts_with(ARMOR_BEGIN) {
+                let (shift, body) = dearmor(&text)?;
+                (Some(shift), body)
+            } else if let Some((shift, body)) = parse_embedded_shift(&text) {
+                (Some(shift), body.to_string())
+            } else {
+                (None, text)
+            };
+            let shift = match embedded {
+                Some(shift) if !explicit => shift,
+                _ => args.resolve_shift()?,
+            };
+            run_cipher(&args, &body, shift, Direction::Backward, Framing::Plain)?;
+        }
+        Commands::BruteForce {
+            input,
+            best_only,
+            output,
+            output_format,
+        } => {
+            let text = get_input_text(&input)?;
+            if best_only {
+                let (_, plain_text) = crack(&text);
+                output_result(&plain_text, output.as_deref(), false, false)?;
+            } else {
+                let candidates = brute_force(&text);
+                let formatted = match output_format.unwrap_or(BruteFormat::Lines) {
+                    BruteFormat::Lines => format_brute_lines(&candidates),
+                    BruteFormat::Table => format_brute_table(&candidates),
+                    BruteFormat::Csv => format_brute_csv(&candidates),
+                };
+                output_result(&formatted, output.as_deref(), false, false)?;
+            }
+        }
+        Commands::Try { input, shift_range } => {
+            let text = get_input_text(&input)?;
+            let (start, end) = shift_range;
+            for shift in start..=end {
+                println!("Shift {:2}: {}", shift, decrypt(&text, shift));
+            }
+        }
+        Commands::Cascade {
+            file,
+            base_shift,
+            step,
+        } => {
+            let text = read_file(&file, InputEncoding::Utf8)?;
+            let base_shift = base_shift.unwrap_or_else(default_shift_from_env);
+            print!("{}", encrypt_lines_cascading(&text, base_shift, step));
+        }
+        Commands::A1z26 { input, decode } => {
+            let text = get_input_text(&input)?;
+            if decode {
+                println!("{}", a1z26::decode(&text)?);
+            } else {
+                println!("{}", a1z26::encode(&text));
+            }
+        }
+        Commands::Table { shift } => {
+            let (plain, cipher) = cipher_table(shift.unwrap_or_else(default_shift_from_env));
+            println!("{}", plain);
+            println!("{}", cipher);
+        }
+        Commands::FullTable => print!("{}", full_tabula_recta()),
+        Commands::Analyze {
+            input,
+            chart,
+            #[cfg(feature = "json")]
+            stats_json,
+            columns,
+        } => {
+            let text = get_input_text(&input)?;
+            #[cfg(feature = "json")]
+            if stats_json {
+                print_stats_json(&text)?;
+                return Ok(());
+            }
+            let freqs = letter_frequencies(&text);
+            if chart {
+                print!("{}", frequency_chart(&freqs, 50));
+            } else if let Some(cols) = columns {
+                let entries: Vec<String> = ('A'..='Z')
+                    .zip(freqs)
+                    .map(|(letter, freq)| format!("{}: {:6.2}%", letter, freq * 100.0))
+                    .collect();
+                print!("{}", tabulate(&entries, cols));
+            } else {
+                for (letter, freq) in ('A'..='Z').zip(freqs) {
+                    println!("{}: {:6.2}%", letter, freq * 100.0);
+                }
+            }
+        }
+        Commands::Interactive { history } => run_interactive_mode(history.as_deref())?,
+        Commands::Crack {
+            input,
+            summary,
+            dictionary,
+        } => {
+            let text = get_input_text(&input)?;
+            if summary {
+                print!("{}", crack_summary(&text));
+            } else {
+                let (shift, plain_text) = match dictionary {
+                    Some(path) => {
+                        let words = load_dictionary(&path)?;
+                        let shift = best_shift_with_dictionary(&text, &words);
+                        (shift, decrypt(&text, shift))
+                    }
+                    None => crack(&text),
+                };
+                println!("Detected shift: {}", shift);
+                println!("{}", plain_text);
+            }
+        }
+        Commands::Ic { input } => {
+            let text = get_input_text(&input)?;
+            println!("{:.6}", index_of_coincidence(&text));
+        }
+        Commands::Batch {
+            files,
+            out_dir,
+            shift,
+            decrypt: decrypt_files,
+            suffix,
+        } => {
+            let shift = shift.unwrap_or_else(default_shift_from_env);
+            fs::create_dir_all(&out_dir)
+                .map_err(|e| CipherError::Io(format!("failed to create {}: {}", out_dir, e)))?;
+            // work out every destination first, so nothing is written
+            // when one of them would replace its own input or another output
+            let mut jobs = Vec::new();
+            let mut destinations = HashSet::new();
+            for file in files {
+                let name = Path::new(&file)
+                    .file_name()
+                    .ok_or_else(|| CipherError::Io(format!("{} is not a file", file)))?;
+                let name = match &suffix {
+                    Some(suffix) => apply_suffix(name, suffix),
+                    None => name.to_os_string(),
+                };
+                let path = Path::new(&out_dir).join(name);
+                if is_same_file(Path::new(&file), &path) {
+                    return Err(format!(
+                        "{} would overwrite its input, use --suffix or another --out-dir",
+                        path.display()
+                    )
+                    .into());
+                }
+                if !destinations.insert(path.clone()) {
+                    return Err(format!(
+                        "more than one input would be written to {}",
+                        path.display()
+                    )
+                    .into());
+                }
+                jobs.push((file, path));
+            }
+            for (file, path) in jobs {
+                let text = read_file(&file, InputEncoding::Utf8)?;
+                let result = if decrypt_files {
+                    decrypt(&text, shift)
+                } else {
+                    encrypt(&text, shift)
+                };
+                fs::write(&path, result).map_err(|e| {
+                    CipherError::Io(format!("failed to write {}: {}", path.display(), e))
+                })?;
+                println!("Result written to file: {}", path.display());
+            }
+        }
+        Commands::Diff { text, shift } => {
+            let shift = shift.unwrap_or_else(default_shift_from_env);
+            let marks: String = change_mask(&text, shift)
+                .into_iter()
+                .map(|changed| if changed { '^' } else { ' ' })
+                .collect();
+            println!("{}", text);
+            println!("{}", encrypt(&text, shift));
+            println!("{}", marks.trim_end());
+        }
+        Commands::Compare { text, shift } => {
+            let shift = shift.unwrap_or_else(default_shift_from_env);
+            print!("{}", side_by_side(&text, shift));
+        }
+        Commands::FindShift { plain, cipher } => match are_caesar_related(&plain, &cipher) {
+            Some(shift) => println!("{}", shift),
+            None => return Err("the texts are not related by a Caesar shift".into()),
+        },
+        Commands::EffectiveShift { shift } => println!("{}", normalize_shift(shift)),
+        Commands::Substitute { input, map_file } => {
+            let text = get_input_text(&input)?;
+            let mapping = load_mapping(&map_file)?;
+            println!("{}", substitute(&text, &mapping));
+        }
+        Commands::Csv {
+            file,
+            column,
+            shift,
+            include_header,
+            output,
+        } => {
+            let text = read_file(&file, InputEncoding::Utf8)?;
+            let shift = shift.unwrap_or_else(default_shift_from_env);
+            let result = encrypt_csv_column(&text, column, shift, include_header)?;
+            output_result(
+                result.trim_end_matches('\n'),
+                output.as_deref(),
+                false,
+                false,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// how `run_cipher` records the shift in its output.
+enum Framing {
+    Plain,
+    /// a leading "#shift:N" line, see `parse_embedded_shift`.
+    EmbedShift,
+    /// an `armor` block.
+    Armor,
+}
+
+/// shift `text` by `shift` in direction `dir` and apply the output options.
+/// the framing is added before any hex or Base64 encoding.
+fn run_cipher(
+    args: &CipherArgs,
+    text: &str,
+    shift: i16,
+    dir: Direction,
+    framing: Framing,
+) -> Result<(), Box<dyn Error>> {
+    let user_shift = shift;
+    let shift = match dir {
+        Direction::Forward => shift,
+        Direction::Backward => -normalize_shift(shift),
+    };
+    if args.strict_ascii {
+        ensure_ascii(text)?;
+    } else if !args.quiet && contains_non_latin_letters(text) {
+        eprintln!("warning: letters outside A-Z are not shifted");
+    }
+    let mut result = match args.only_first {
+        Some(n) => encrypt_prefix(text, shift, n),
+        None => CipherBuilder::new()
+            .shift(shift)
+            .preserve_case(!args.uppercase)
+            .lowercase(args.lowercase)
+            .strip_non_alpha(args.strip)
+            .shift_upper(!args.lower_only)
+            .shift_lower(!args.upper_only)
+            .encrypt(text),
+    };
+    if let Some(group_size) = args.group {
+        result = group_letters(&result, group_size);
+    }
+    if let Some(cols) = args.wrap {
+        result = wrap_text(&result, cols);
+    }
+    match framing {
+        Framing::Plain => {}
+        Framing::EmbedShift => {
+            result = format!("#shift:{}\n{}", normalize_shift(shift), result);
+        }
+        Framing::Armor => result = armor(&result, normalize_shift(shift)),
+    }
+    if args.hex_out {
+        result = encode_hex(&result);
+    } else if args.base64_out {
+        result = encode_base64(&result);
+    }
+    output_result(&result, args.output.as_deref(), args.quiet, args.append)?;
+
+    if args.verbose {
+        let letters = args.shifted_letters(text);
+        eprintln!("effective shift: {}", normalize_shift(user_shift));
+        eprintln!("letters transformed: {}", letters);
+        eprintln!(
+            "characters passed through: {}",
+            text.chars().count() - letters
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+fn print_stats_json(text: &str) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string(&frequency_stats(text))?);
+    Ok(())
+}
+
+/// encrypt the `--file` input chunk by chunk, printing a percentage to stderr
+/// when the file size is known.
+fn run_stream_with_progress(args: &CipherArgs, shift: i16) -> Result<(), Box<dyn Error>> {
+    let path = args
+        .input
+        .file
+        .as_deref()
+        .ok_or("--progress needs --file")?;
+    if let Some(output) = args.output.as_deref() {
+        // opening the output would truncate the input before it is read
+        if is_same_file(Path::new(path), Path::new(output)) {
+            return Err(format!("{} is also the input, write the result elsewhere", output).into());
+        }
+    }
+    let file =
+        File::open(path).map_err(|e| CipherError::Io(format!("failed to read {}: {}", path, e)))?;
+    let mut input = LetterScan::new(file);
+    let size = input.inner.metadata().map(|m| m.len()).unwrap_or(0);
+    let report = |done: u64| {
+        if let Some(percent) = (done * 100).checked_div(size) {
+            eprint!("\r{:3}%", percent);
+        }
+    };
+    match args.output.as_deref() {
+        Some(output) => {
+            let file = File::create(output)
+                .map_err(|e| CipherError::Io(format!("failed to write {}: {}", output, e)))?;
+            encrypt_stream_with_progress(&mut input, BufWriter::new(file), shift, report)
+                .map_err(|e| CipherError::Io(format!("failed to write {}: {}", output, e)))?;
+            eprintln!();
+            if !args.quiet {
+                println!("Result written to file: {}", output);
+            }
+        }
+        None => {
+            encrypt_stream_with_progress(&mut input, io::stdout().lock(), shift, report)?;
+            eprintln!();
+        }
+    }
+    if !args.quiet && input.found {
+        eprintln!("warning: letters outside A-Z are not shifted");
+    }
+    Ok(())
+}
+
+/// a reader that notes whether the UTF-8 passing through it has letters
+/// outside A-Z, so streamed input gets the same warning as `run_cipher` gives.
+struct LetterScan<R> {
+    inner: R,
+    /// the start of a character split across two reads.
+    pending: Vec<u8>,
+    found: bool,
+}
+
+impl<R> LetterScan<R> {
+    fn new(inner: R) -> Self {
+        LetterScan {
+            inner,
+            pending: Vec::new(),
+            found: false,
+        }
+    }
+
+    fn scan(&mut self, bytes: &[u8]) {
+        if self.found {
+            return;
+        }
+        self.pending.extend_from_slice(bytes);
+        let mut rest = &self.pending[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    self.found |= contains_non_latin_letters(text);
+                    rest = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    let valid = std::str::from_utf8(valid).unwrap_or_default();
+                    self.found |= contains_non_latin_letters(valid);
+                    match e.error_len() {
+                        // invalid bytes pass through unshifted, skip them
+                        Some(len) => rest = &after[len..],
+                        // an incomplete character, finish it with the next read
+                        None => {
+                            rest = after;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        self.pending = rest.to_vec();
+    }
+}
+
+impl<R: Read> Read for LetterScan<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.scan(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// print `result`, or write it to `output` when a path is given.
+/// with `append` the result is added to the end of the file as its own line
+/// instead of replacing the contents. a file written without `append` does
+/// not end in a newline, so one is put in front of the result in that case.
+pub fn output_result(
+    result: &str,
+    output: Option<&str>,
+    quiet: bool,
+    append: bool,
+) -> Result<(), CipherError> {
+    match output {
+        Some(path) => {
+            log::debug!("writing the result to {} (append: {})", path, append);
+            let written = if append {
+                OpenOptions::new()
+                    .create(true)
+                    .read(true)
+                    .append(true)
+                    .open(path)
+                    .and_then(|mut file| {
+                        if ends_without_newline(&mut file)? {
+                            writeln!(file)?;
+                        }
+                        writeln!(file, "{}", result)
+                    })
+            } else {
+                fs::write(path, result)
+            };
+            written.map_err(|e| CipherError::Io(format!("failed to write {}: {}", path, e)))?;
+            if !quiet {
+                println!("Result written to file: {}", path);
+            }
+        }
+        None => {
+            log::debug!("printing the result to stdout");
+            println!("{}", result);
+        }
+    }
+    Ok(())
+}
+
+/// whether both paths exist and lead to the same file.
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// whether `file` is non-empty and its last byte is not a newline.
+fn ends_without_newline(file: &mut File) -> io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(false);
+    }
+    let mut last = [0u8];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+/// read the input and undo any transport encoding.
+fn get_cipher_input(args: &CipherArgs) -> Result<String, Box<dyn Error>> {
+    let text = get_input_text_with_encoding(&args.input, args.input_encoding)?;
+    if args.hex_in {
+        Ok(decode_hex(text.trim())?)
+    } else if args.base64_in {
+        Ok(decode_base64(text.trim())?)
+    } else {
+        Ok(text)
+    }
+}
+
+/// the candidates as "Shift N: TEXT" lines, in the order given.
+pub fn format_brute_lines(candidates: &[(i16, String)]) -> String {
+    let lines: Vec<String> = candidates
+        .iter()
+        .map(|(shift, plain_text)| format!("Shift {:2}: {}", shift, plain_text))
+        .collect();
+    lines.join("\n")
+}
+
+/// the candidates in a "Shift  Text" table with the shifts right-aligned.
+pub fn format_brute_table(candidates: &[(i16, String)]) -> String {
+    let mut table = String::from("Shift  Text");
+    for (shift, plain_text) in candidates {
+        table += &format!("\n{:>5}  {}", shift, plain_text);
+    }
+    table
+}
+
+/// the candidates as CSV with a "shift,text" header. texts containing commas,
+/// quotes or line breaks are quoted.
+pub fn format_brute_csv(candidates: &[(i16, String)]) -> String {
+    const IN_MEMORY: &str = "writing CSV to memory cannot fail";
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["shift", "text"]).expect(IN_MEMORY);
+    for (shift, plain_text) in candidates {
+        writer
+            .write_record([shift.to_string().as_str(), plain_text])
+            .expect(IN_MEMORY);
+    }
+    let bytes = writer.into_inner().expect(IN_MEMORY);
+    let text = String::from_utf8(bytes).expect("the CSV fields are UTF-8");
+    text.trim_end_matches('\n').to_string()
+}
+
+pub fn get_input_text(input: &InputArgs) -> Result<String, CipherError> {
+    get_input_text_with_encoding(input, InputEncoding::Utf8)
+}
+
+pub fn get_input_text_with_encoding(
+    input: &InputArgs,
+    encoding: InputEncoding,
+) -> Result<String, CipherError> {
+    match (&input.text, &input.file, &input.text_env) {
+        (Some(text), _, _) => {
+            log::debug!("reading the input from --text");
+            Ok(text.clone())
+        }
+        (None, Some(path), _) => {
+            log::debug!("reading the input from {} ({:?})", path, encoding);
+            read_file(path, encoding)
+        }
+        (None, None, Some(name)) => {
+            log::debug!("reading the input from ${}", name);
+            read_env_text(name)
+        }
+        // clap rejects this, but the struct can be built by hand
+        (None, None, None) => Err(CipherError::EmptyText),
+    }
+}
+
+/// the value of the environment variable `name`, or an error saying why
+/// it could not be read.
+pub fn read_env_text(name: &str) -> Result<String, CipherError> {
+    std::env::var(name).map_err(|e| CipherError::Io(format!("failed to read ${}: {}", name, e)))
+}
+
+/// encrypt the cells of `column` (from 0) in CSV `text`. the first row is
+/// treated as a header and kept unless `include_header` is set.
+/// rows too short to have the column are kept as they are.
+pub fn encrypt_csv_column(
+    text: &str,
+    column: usize,
+    shift: i16,
+    include_header: bool,
+) -> Result<String, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .from_writer(Vec::new());
+    for (row, record) in reader.records().enumerate() {
+        let record = record?;
+        if row == 0 && !include_header {
+            writer.write_record(&record)?;
+            continue;
+        }
+        writer.write_record(record.iter().enumerate().map(|(i, cell)| {
+            if i == column {
+                encrypt(cell, shift)
+            } else {
+                cell.to_string()
+            }
+        }))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// insert `suffix` before the extension: ("notes.txt", "enc") gives "notes.enc.txt".
+/// names without an extension get it at the end, "README" gives "README.enc".
+pub fn apply_suffix(name: &OsStr, suffix: &str) -> OsString {
+    let path = Path::new(name);
+    let mut renamed = path.file_stem().unwrap_or(name).to_os_string();
+    renamed.push(".");
+    renamed.push(suffix.trim_start_matches('.'));
+    if let Some(extension) = path.extension() {
+        renamed.push(".");
+        renamed.push(extension);
+    }
+    renamed
+}
+
+/// lay `entries` out left to right in rows of `cols`, padding every column
+/// to the widest entry. `cols == 0` gives a single column.
+pub fn tabulate(entries: &[String], cols: usize) -> String {
+    let width = entries.iter().map(|e| e.chars().count()).max().unwrap_or(0);
+    entries
+        .chunks(cols.max(1))
+        .map(|row| {
+            let line: Vec<String> = row.iter().map(|e| format!("{:<width$}", e)).collect();
+            line.join("  ").trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+/// split the first line off `text` and parse it as the shift, for files
+/// that store the key on line one. returns the shift and the remaining lines,
+/// without the file's final line ending.
+pub fn split_shift_line(text: &str) -> Result<(i16, String), CipherError> {
+    let (line, body) = text.split_once('\n').unwrap_or((text, ""));
+    let body = body
+        .strip_suffix("\r\n")
+        .or_else(|| body.strip_suffix('\n'))
+        .unwrap_or(body);
+    Ok((parse_shift(line)?, body.to_string()))
+}
+
+/// split a leading "#shift:N" line off `text`, as written by `--embed-shift`.
+/// returns the shift and the rest of the text.
+pub fn parse_embedded_shift(text: &str) -> Option<(i16, &str)> {
+    let rest = text.strip_prefix("#shift:")?;
+    let (line, body) = rest.split_once('\n').unwrap_or((rest, ""));
+    let shift = line.trim().parse().ok()?;
+    Some((shift, body))
+}
+
+/// parse a shift given on the command line, with a friendlier message
+/// than clap's default one.
+pub fn parse_shift(s: &str) -> Result<i16, CipherError> {
+    s.trim()
+        .parse()
+        .map_err(|_| CipherError::InvalidShift(format!("'{}' is not a valid integer shift", s)))
+}
+
+/// parse "START:END" into an inclusive shift range.
+pub fn parse_shift_range(s: &str) -> Result<(i16, i16), CipherError> {
+    let invalid = || CipherError::InvalidShift(format!("'{}' is not a START:END range", s));
+    let (start, end) = s.split_once(':').ok_or_else(invalid)?;
+    let start: i16 = start.trim().parse().map_err(|_| invalid())?;
+    let end: i16 = end.trim().parse().map_err(|_| invalid())?;
+    if start > end {
+        return Err(CipherError::InvalidShift(format!(
+            "range start {} is greater than end {}",
+            start, end
+        )));
+    }
+    Ok((start, end))
+}
+
+fn read_file(path: &str, encoding: InputEncoding) -> Result<String, CipherError> {
+    let bytes =
+        fs::read(path).map_err(|e| CipherError::Io(format!("failed to read {}: {}", path, e)))?;
+    match encoding {
+        InputEncoding::Utf8 => String::from_utf8(bytes).map_err(|_| {
+            CipherError::InvalidEncoding(format!(
+                "{} is not valid UTF-8, try --input-encoding latin1",
+                path
+            ))
+        }),
+        InputEncoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+    }
+}
+
+/// read a substitution alphabet: 26 distinct letters, the replacements for
+/// A to Z in order. whitespace is ignored and the letters are uppercased.
+pub fn load_mapping(path: &str) -> Result<[char; 26], CipherError> {
+    let text = read_file(path, InputEncoding::Utf8)?;
+    parse_mapping(&text)
+        .map_err(|msg| CipherError::InvalidEncoding(format!("mapping in {}: {}", path, msg)))
+}
+
+fn parse_mapping(text: &str) -> Result<[char; 26], String> {
+    let letters: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if letters.len() != 26 {
+        return Err(format!("expected 26 letters, found {}", letters.len()));
+    }
+    let mut mapping = ['A'; 26];
+    for (i, &c) in letters.iter().enumerate() {
+        if !c.is_ascii_uppercase() {
+            return Err(format!("'{}' is not a letter", c));
+        }
+        if letters[..i].contains(&c) {
+            return Err(format!("'{}' appears more than once", c));
+        }
+        mapping[i] = c;
+    }
+    Ok(mapping)
+}
+
+/// read a word list, one or more words per line, lowercased.
+pub fn load_dictionary(path: &str) -> Result<HashSet<String>, CipherError> {
+    let text = read_file(path, InputEncoding::Utf8)?;
+    Ok(text.split_whitespace().map(str::to_lowercase).collect())
+}
+
+/// decode hex digits (either case) into UTF-8 text.
+pub fn decode_hex(s: &str) -> Result<String, CipherError> {
+    if !s.len().is_multiple_of(2) {
+        return Err(CipherError::InvalidEncoding(
+            "hex input has an odd number of digits".to_string(),
+        ));
+    }
+    let bytes = (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .filter(|pair| pair.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| {
+                    CipherError::InvalidEncoding(format!("invalid hex digits at offset {}", i))
+                })
+        })
+        .collect::<Result<Vec<u8>, CipherError>>()?;
+    String::from_utf8(bytes)
+        .map_err(|_| CipherError::InvalidEncoding("hex input is not valid UTF-8".to_string()))
+}
+
+/// encode the UTF-8 bytes of `s` as lowercase hex.
+pub fn encode_hex(s: &str) -> String {
+    s.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// decode standard Base64 into UTF-8 text.
+pub fn decode_base64(s: &str) -> Result<String, CipherError> {
+    let bytes = BASE64
+        .decode(s)
+        .map_err(|e| CipherError::InvalidEncoding(format!("invalid Base64: {}", e)))?;
+    String::from_utf8(bytes)
+        .map_err(|_| CipherError::InvalidEncoding("Base64 input is not valid UTF-8".to_string()))
+}
+
+/// encode the UTF-8 bytes of `s` as standard Base64.
+pub fn encode_base64(s: &str) -> String {
+    BASE64.encode(s)
+}
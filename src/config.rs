@@ -0,0 +1,17 @@
+/// shift used when none is given.
+pub const DEFAULT_SHIFT: i16 = 3;
+
+/// environment variable that overrides `DEFAULT_SHIFT`.
+#[cfg(feature = "std")]
+pub const DEFAULT_SHIFT_ENV: &str = "CAESAR_DEFAULT_SHIFT";
+
+/// the shift from `CAESAR_DEFAULT_SHIFT`, normalized to 0..=25.
+/// falls back to `DEFAULT_SHIFT` when the variable is unset or not a number.
+#[cfg(feature = "std")]
+pub fn default_shift_from_env() -> i16 {
+    let shift = std::env::var(DEFAULT_SHIFT_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_SHIFT);
+    crate::caesar_cipher::normalize_shift(shift)
+}
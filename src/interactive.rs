@@ -0,0 +1,93 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, Write};
+
+use crate::caesar_cipher::{decrypt, encrypt};
+use crate::config::default_shift_from_env;
+
+/// run the menu on stdin/stdout. with `history`, one line per operation
+/// is appended to that file.
+pub fn run_interactive_mode(history: Option<&str>) -> io::Result<()> {
+    let history = match history {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+    interactive_loop(io::stdin().lock(), io::stdout(), history)
+}
+
+/// menu loop behind `run_interactive_mode`, generic over its input and output
+/// so it can be driven from tests. ends on `q` or at the end of the input (Ctrl-D).
+/// `t` toggles trimming of the entered text, so leading and trailing spaces
+/// can be encrypted too.
+/// every operation is logged to `history` as e.g. "encrypt shift=3 len=11",
+/// without the text itself.
+pub fn interactive_loop<R: BufRead, W: Write>(
+    mut input: R,
+    mut output: W,
+    mut history: Option<File>,
+) -> io::Result<()> {
+    let mut last_shift = default_shift_from_env();
+    let mut trim_input = true;
+    loop {
+        writeln!(
+            output,
+            "[e] encrypt  [d] decrypt  [t] toggle trimming  [q] quit"
+        )?;
+        let Some(choice) = prompt(&mut input, &mut output, "> ")? else {
+            break;
+        };
+        match choice.trim() {
+            operation @ ("e" | "d") => {
+                let Some(text) = prompt(&mut input, &mut output, "Enter text: ")? else {
+                    break;
+                };
+                let shift_prompt = format!("Enter shift value [{}]: ", last_shift);
+                let Some(shift_line) = prompt(&mut input, &mut output, &shift_prompt)? else {
+                    break;
+                };
+                if let Ok(shift) = shift_line.trim().parse() {
+                    last_shift = shift;
+                }
+                let text = if trim_input {
+                    text.trim()
+                } else {
+                    text.trim_end_matches(['\n', '\r'])
+                };
+                let (name, result) = if operation == "e" {
+                    ("encrypt", encrypt(text, last_shift))
+                } else {
+                    ("decrypt", decrypt(text, last_shift))
+                };
+                if let Some(history) = &mut history {
+                    let len = text.chars().count();
+                    writeln!(history, "{} shift={} len={}", name, last_shift, len)?;
+                }
+                writeln!(output, "Result: {}", result)?;
+            }
+            "t" => {
+                trim_input = !trim_input;
+                let state = if trim_input { "on" } else { "off" };
+                writeln!(output, "Trimming input: {}", state)?;
+            }
+            "q" => break,
+            other => writeln!(output, "Unknown option: {}", other)?,
+        }
+    }
+    writeln!(output, "Goodbye!")
+}
+
+/// print `message` and read one line; `None` at the end of the input.
+fn prompt<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    message: &str,
+) -> io::Result<Option<String>> {
+    write!(output, "{}", message)?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        // end of input
+        writeln!(output)?;
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
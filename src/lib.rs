@@ -1 +1,37 @@
+//! # caesar_cipher_enc_dec
+//!
+//! the cipher functions in `caesar_cipher` only need `alloc`, so the crate
+//! can be used without the standard library:
+//!
+//! ```toml
+//! caesar_cipher_enc_dec = { version = "0.7", default-features = false }
+//! ```
+//!
+//! the `std` feature (on by default) adds the `std::error::Error` impl
+//! for `CipherError` and the `cli` and `interactive` modules.
+//!
+//! the functions that return transformed text (`encrypt`, `decrypt` and
+//! helpers such as `group_letters`, `wrap_text`, `armor` or `a1z26::encode`)
+//! are `#[must_use]`, since they never change their input:
+//! `encrypt(text, 3);` on its own does nothing.
+//!
+//! the optional `serde` feature derives `Serialize`/`Deserialize` for `CipherError`
+//! and `FrequencyStats`, the optional `json` feature adds `serde_json` on top
+//! for `analyze --stats-json`,
+//! the optional `rayon` feature adds `brute_force_parallel`, and the optional
+//! `unicode-normalization` feature adds `encrypt_unicode_normalize`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+/// the crate version, as set in Cargo.toml.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub mod a1z26;
+pub mod alphabet;
 pub mod caesar_cipher;
+#[cfg(feature = "std")]
+pub mod cli;
+pub mod config;
+#[cfg(feature = "std")]
+pub mod interactive;
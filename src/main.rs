@@ -1,8 +1,24 @@
 use caesar_cipher_enc_dec::caesar_cipher::{decrypt, encrypt};
+use caesar_cipher_enc_dec::cli::{run_cli, Cli};
+use clap::Parser;
+
 fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(command) => {
+            if let Err(e) = run_cli(command) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        None => demo(),
+    }
+}
+
+fn demo() {
     // 暗号化と復号化 --- (*1)
     let text: &str = "I LOVE YOU.";
-    let enc_text: String = encrypt(&text, 3); // 暗号化
+    let enc_text: String = encrypt(text, 3); // 暗号化
     let dec_text: String = encrypt(&enc_text, -3); // 復号化
     let dec_text2: String = decrypt(&enc_text, 3);
     println!("文字列: {}", text);
@@ -0,0 +1,1013 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{self, Command, Output};
+use std::sync::Mutex;
+
+use caesar_cipher_enc_dec::caesar_cipher::{encrypt, CipherError};
+use caesar_cipher_enc_dec::cli::{
+    apply_suffix, decode_base64, decode_hex, encode_base64, encode_hex, encrypt_csv_column,
+    format_brute_csv, format_brute_lines, format_brute_table, get_input_text, load_mapping,
+    output_result, parse_embedded_shift, parse_shift, parse_shift_range, read_env_text, run_cli,
+    split_shift_line, tabulate, Cli, InputArgs,
+};
+use caesar_cipher_enc_dec::interactive::interactive_loop;
+use clap::Parser;
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_caesar_cipher_enc_dec"))
+        .args(args)
+        .output()
+        .expect("failed to run the binary")
+}
+
+/// a per-process path in the temp dir, removed if left over from a previous run.
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("caesar_cli_{}_{}", process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_cli_verbose_reports_counts() {
+    let output = run(&[
+        "encrypt",
+        "--text",
+        "Hello, World!",
+        "--shift",
+        "29",
+        "--verbose",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success());
+    assert_eq!("Khoor, Zruog!\n", stdout);
+    assert!(stderr.contains("effective shift: 3"));
+    assert!(stderr.contains("letters transformed: 10"));
+    assert!(stderr.contains("characters passed through: 3"));
+
+    let output = run(&["decrypt", "-t", "Khoor", "-s", "3", "--verbose"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success());
+    assert_eq!("Hello\n", String::from_utf8_lossy(&output.stdout));
+    assert!(stderr.contains("effective shift: 3\n"), "{}", stderr);
+}
+
+#[test]
+fn test_parse_shift_range() {
+    assert_eq!(Ok((3, 7)), parse_shift_range("3:7"));
+    assert_eq!(Ok((-2, 2)), parse_shift_range("-2:2"));
+    assert!(parse_shift_range("7:3").is_err());
+    assert!(parse_shift_range("3-7").is_err());
+    assert!(parse_shift_range("a:7").is_err());
+}
+
+#[test]
+fn test_cli_try_shift_range() {
+    let output = run(&["try", "--text", "Khoor", "--shift-range", "2:4"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert_eq!(3, stdout.lines().count());
+    assert!(stdout.contains("Shift  3: Hello"));
+}
+
+#[test]
+fn test_hex_codec() {
+    assert_eq!("4b686f6f72", encode_hex("Khoor"));
+    assert_eq!(Ok("Khoor".to_string()), decode_hex("4B686F6F72"));
+    assert!(decode_hex("4b6").is_err());
+    assert!(decode_hex("zz").is_err());
+    assert!(decode_hex("ff").is_err());
+    assert!(decode_hex("+f").is_err());
+}
+
+#[test]
+fn test_cli_hex_in_decrypts() {
+    let output = run(&[
+        "decrypt",
+        "--text",
+        "4b686f6f72",
+        "--shift",
+        "3",
+        "--hex-in",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!("Hello\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_cli_invalid_hex_fails() {
+    let output = run(&["decrypt", "--text", "4g", "--shift", "3", "--hex-in"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid encoding"));
+}
+
+#[test]
+fn test_base64_codec() {
+    assert_eq!("S2hvb3I=", encode_base64("Khoor"));
+    assert_eq!(Ok("Khoor".to_string()), decode_base64("S2hvb3I="));
+    assert!(decode_base64("not base64!").is_err());
+}
+
+#[test]
+fn test_cli_base64_roundtrip() {
+    let encrypted = run(&[
+        "encrypt",
+        "--text",
+        "Hello, World!",
+        "--shift",
+        "5",
+        "--base64-out",
+    ]);
+    let encoded = String::from_utf8_lossy(&encrypted.stdout)
+        .trim()
+        .to_string();
+    let decrypted = run(&["decrypt", "--text", &encoded, "--shift", "5", "--base64-in"]);
+
+    assert!(encrypted.status.success());
+    assert!(decrypted.status.success());
+    assert_eq!(
+        "Hello, World!\n",
+        String::from_utf8_lossy(&decrypted.stdout)
+    );
+}
+
+#[test]
+fn test_cli_brute_force_includes_shift_zero() {
+    let output = run(&["brute-force", "--text", "Khoor"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert_eq!(26, stdout.lines().count());
+    assert!(stdout.contains("Shift  0:"));
+    assert!(stdout.contains("Shift 25:"));
+}
+
+#[test]
+fn test_cli_brute_force_shift_zero_is_original() {
+    let output = run(&["brute-force", "--text", "Khoor"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(Some("Shift  0: Khoor"), stdout.lines().next());
+    assert!(stdout.contains("Shift  3: Hello"));
+}
+
+#[test]
+fn test_cli_count_only() {
+    let output = run(&["encrypt", "--text", "Hello, World!", "--count-only"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        "10 of 13 characters would be shifted\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_cli_counts_follow_case_filters() {
+    let output = run(&[
+        "encrypt",
+        "-t",
+        "Hello world",
+        "--upper-only",
+        "--count-only",
+    ]);
+    assert_eq!(
+        "1 of 11 characters would be shifted\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let output = run(&["encrypt", "-t", "Hello world", "--lower-only", "-v"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("letters transformed: 9"), "{}", stderr);
+    assert!(
+        stderr.contains("characters passed through: 2"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_cli_verbose_counts_only_first() {
+    let output = run(&["encrypt", "-t", "Hello world", "--only-first", "1", "-v"]);
+    assert!(output.status.success());
+    assert_eq!("Kello world\n", String::from_utf8_lossy(&output.stdout));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("letters transformed: 1"), "{}", stderr);
+    assert!(
+        stderr.contains("characters passed through: 10"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_interactive_remembers_last_shift() {
+    let input = "e\nHello\n5\ne\nHello\n\nd\nMjqqt\nabc\nq\n";
+    let mut output = Vec::new();
+    interactive_loop(input.as_bytes(), &mut output, None).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Enter shift value [3]:"));
+    assert!(output.contains("Enter shift value [5]:"));
+    assert_eq!(2, output.matches("Result: Mjqqt").count());
+    assert!(output.contains("Result: Hello"));
+    assert!(output.contains("Goodbye!"));
+}
+
+#[test]
+fn test_cli_count_only_writes_no_file() {
+    let path = temp_path("count_only.txt");
+    let output = run(&[
+        "encrypt",
+        "-t",
+        "Hello",
+        "--count-only",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_cli_quiet_output_to_file() {
+    let path = temp_path("quiet.txt");
+    let output = run(&[
+        "encrypt",
+        "-t",
+        "Hello",
+        "-s",
+        "3",
+        "--quiet",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!("Khoor", fs::read_to_string(&path).unwrap());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_encrypt_output_to_file() {
+    let path = temp_path("output.txt");
+    let path_str = path.to_str().unwrap();
+    let output = run(&["encrypt", "-t", "Hello", "-s", "3", "-o", path_str]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        format!("Result written to file: {}\n", path_str),
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!("Khoor", fs::read_to_string(&path).unwrap());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_a1z26_roundtrip() {
+    let encoded = run(&["a1z26", "--text", "hello"]);
+    let numbers = String::from_utf8_lossy(&encoded.stdout).trim().to_string();
+    let decoded = run(&["a1z26", "--decode", "--text", &numbers]);
+
+    assert_eq!("8 5 12 12 15", numbers);
+    assert_eq!("HELLO\n", String::from_utf8_lossy(&decoded.stdout));
+}
+
+#[test]
+fn test_cli_latin1_input() {
+    let path = temp_path("latin1.txt");
+    fs::write(&path, b"Caf\xe9 Khoor").unwrap();
+    let path_str = path.to_str().unwrap();
+
+    let latin1 = run(&[
+        "decrypt",
+        "-f",
+        path_str,
+        "-s",
+        "3",
+        "--input-encoding",
+        "latin1",
+    ]);
+    let utf8 = run(&["decrypt", "-f", path_str, "-s", "3"]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(latin1.status.success());
+    assert_eq!("Zxc\u{e9} Hello\n", String::from_utf8_lossy(&latin1.stdout));
+    assert!(!utf8.status.success());
+    assert!(String::from_utf8_lossy(&utf8.stderr).contains("not valid UTF-8"));
+}
+
+#[test]
+fn test_cli_version_matches_cargo_toml() {
+    let output = run(&["--version"]);
+
+    assert_eq!(env!("CARGO_PKG_VERSION"), caesar_cipher_enc_dec::VERSION);
+    assert_eq!(
+        format!("caesar_cipher_enc_dec {}\n", caesar_cipher_enc_dec::VERSION),
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_cli_crack_with_dictionary() {
+    let path = temp_path("words.txt");
+    fs::write(&path, "hello\nworld\n").unwrap();
+    let output = run(&[
+        "crack",
+        "-t",
+        "Khoor Zruog",
+        "--dictionary",
+        path.to_str().unwrap(),
+    ]);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        "Detected shift: 3\nHello World\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_interactive_stops_at_end_of_input() {
+    for input in ["", "e\n", "e\nHello\n", "d\nKhoor\n3\n"] {
+        let mut output = Vec::new();
+        interactive_loop(input.as_bytes(), &mut output, None).unwrap();
+
+        assert!(String::from_utf8(output).unwrap().ends_with("Goodbye!\n"));
+    }
+}
+
+#[test]
+fn test_cli_upper_only_leaves_lowercase() {
+    let output = run(&["encrypt", "-t", "Hello World", "-s", "3", "--upper-only"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Kello Zorld"
+    );
+}
+
+#[test]
+fn test_cli_nonexistent_file_error_contains_path() {
+    let path = temp_path("missing.txt");
+    let path_str = path.to_str().unwrap();
+    let output = run(&["encrypt", "-f", path_str]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(path_str));
+}
+
+#[test]
+fn test_get_input_text_read_error_is_io() {
+    let path = temp_path("missing_input.txt");
+    let input = InputArgs {
+        text: None,
+        file: Some(path.to_str().unwrap().to_string()),
+        text_env: None,
+    };
+
+    match get_input_text(&input) {
+        Err(CipherError::Io(msg)) => assert!(msg.contains(path.to_str().unwrap())),
+        other => panic!("expected CipherError::Io, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_output_result_write_error_is_io() {
+    let path = temp_path("no_such_dir").join("out.txt");
+
+    match output_result("Khoor", path.to_str(), true, false) {
+        Err(CipherError::Io(msg)) => assert!(msg.contains(path.to_str().unwrap())),
+        other => panic!("expected CipherError::Io, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_cli_append_keeps_previous_results() {
+    let path = temp_path("append.txt");
+    let path_str = path.to_str().unwrap();
+    for text in ["Hello", "World"] {
+        let output = run(&["encrypt", "-t", text, "-s", "3", "-o", path_str, "--append"]);
+        assert!(output.status.success());
+    }
+
+    assert_eq!("Khoor\nZruog\n", fs::read_to_string(&path).unwrap());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_append_after_plain_write_starts_a_new_line() {
+    let path = temp_path("append_after_write.txt");
+    let path_str = path.to_str().unwrap();
+    let output = run(&["encrypt", "-t", "Hello", "-s", "3", "-o", path_str]);
+    assert!(output.status.success());
+    let output = run(&[
+        "encrypt", "-t", "World", "-s", "3", "-o", path_str, "--append",
+    ]);
+    assert!(output.status.success());
+
+    assert_eq!("Khoor\nZruog\n", fs::read_to_string(&path).unwrap());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_apply_suffix() {
+    assert_eq!(apply_suffix(OsStr::new("a.txt"), "enc"), "a.enc.txt");
+    assert_eq!(apply_suffix(OsStr::new("README"), "enc"), "README.enc");
+    assert_eq!(
+        apply_suffix(OsStr::new("notes.txt"), ".dec"),
+        "notes.dec.txt"
+    );
+}
+
+#[test]
+fn test_cli_batch_refuses_to_overwrite_input() {
+    let input = temp_path("batch_in_place.txt");
+    fs::write(&input, "Hello").unwrap();
+    let dir = input.parent().unwrap().to_str().unwrap();
+    let output = run(&["batch", input.to_str().unwrap(), "--out-dir", dir]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("would overwrite its input"));
+    assert_eq!("Hello", fs::read_to_string(&input).unwrap());
+    fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_cli_batch_refuses_clashing_outputs() {
+    let root = temp_path("batch_clash");
+    let _ = fs::remove_dir_all(&root);
+    for dir in ["a", "b"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("notes.txt"), "Hello").unwrap();
+    }
+    let out_dir = root.join("out");
+    let output = run(&[
+        "batch",
+        root.join("a/notes.txt").to_str().unwrap(),
+        root.join("b/notes.txt").to_str().unwrap(),
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+    ]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("more than one input"));
+    assert!(!out_dir.join("notes.txt").exists());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_cli_batch_writes_suffixed_files() {
+    let input = temp_path("batch.txt");
+    let out_dir = temp_path("batch_out");
+    fs::write(&input, "Hello").unwrap();
+    let output = run(&[
+        "batch",
+        input.to_str().unwrap(),
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+        "--suffix",
+        "enc",
+    ]);
+
+    assert!(output.status.success());
+    let name = format!("caesar_cli_{}_batch.enc.txt", process::id());
+    assert_eq!("Khoor", fs::read_to_string(out_dir.join(name)).unwrap());
+    fs::remove_file(&input).unwrap();
+    fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn test_cli_progress_streams_file() {
+    let input = temp_path("progress_in.txt");
+    let output_path = temp_path("progress_out.txt");
+    fs::write(&input, "Hello World\n").unwrap();
+    let output = run(&[
+        "encrypt",
+        "-f",
+        input.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "--progress",
+    ]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("100%"));
+    assert_eq!("Khoor Zruog\n", fs::read_to_string(&output_path).unwrap());
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&output_path).unwrap();
+}
+
+#[test]
+fn test_cli_progress_refuses_to_overwrite_input() {
+    let input = temp_path("progress_same.txt");
+    fs::write(&input, "Hello World\n").unwrap();
+    let path = input.to_str().unwrap();
+    let output = run(&["encrypt", "-f", path, "-o", path, "--progress"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is also the input"));
+    assert_eq!("Hello World\n", fs::read_to_string(&input).unwrap());
+    fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_cli_progress_warns_about_non_latin_letters() {
+    let input = temp_path("progress_cafe.txt");
+    fs::write(&input, "caf\u{e9}\n").unwrap();
+    let output = run(&["encrypt", "-f", input.to_str().unwrap(), "--progress"]);
+
+    assert!(output.status.success());
+    assert_eq!("fdi\u{e9}\n", String::from_utf8_lossy(&output.stdout));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("letters outside A-Z"));
+    fs::remove_file(&input).unwrap();
+
+    let output = run(&["encrypt", "-f", "x", "--progress", "--verbose"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_brute_force_best_only() {
+    let enc_text = encrypt("Attack at dawn, the enemy is near", 11);
+    let output = run(&["brute-force", "--text", &enc_text, "--best-only"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        "Attack at dawn, the enemy is near\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_encrypt_csv_column() {
+    let text = "id,name,city\n1,Alice,Paris\n2,\"Bob, Jr.\",Rome\n";
+
+    assert_eq!(
+        encrypt_csv_column(text, 1, 3, false).unwrap(),
+        "id,name,city\n1,Dolfh,Paris\n2,\"Ere, Mu.\",Rome\n"
+    );
+    assert_eq!(
+        encrypt_csv_column(text, 1, 3, true).unwrap(),
+        "id,qdph,city\n1,Dolfh,Paris\n2,\"Ere, Mu.\",Rome\n"
+    );
+}
+
+#[test]
+fn test_cli_csv_encrypts_one_column() {
+    let path = temp_path("people.csv");
+    fs::write(&path, "id,name,city\n1,Alice,Paris\n").unwrap();
+    let output = run(&["csv", "-f", path.to_str().unwrap(), "--column", "1"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        "id,name,city\n1,Dolfh,Paris\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_csv_keeps_trailing_whitespace() {
+    let path = temp_path("trailing.csv");
+    fs::write(&path, "a,b\n1,x \n").unwrap();
+    let output = run(&["csv", "-f", path.to_str().unwrap(), "--column", "0"]);
+
+    assert!(output.status.success());
+    assert_eq!("a,b\n1,x \n", String::from_utf8_lossy(&output.stdout));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_parse_embedded_shift() {
+    assert_eq!(parse_embedded_shift("#shift:3\nKhoor"), Some((3, "Khoor")));
+    assert_eq!(
+        parse_embedded_shift("#shift:7\r\nKhoor"),
+        Some((7, "Khoor"))
+    );
+    assert_eq!(parse_embedded_shift("#shift:x\nKhoor"), None);
+    assert_eq!(parse_embedded_shift("Khoor"), None);
+}
+
+#[test]
+fn test_cli_embed_shift_roundtrip() {
+    let path = temp_path("embedded.txt");
+    let path_str = path.to_str().unwrap();
+    let output = run(&[
+        "encrypt",
+        "-t",
+        "Hello World",
+        "-s",
+        "11",
+        "--embed-shift",
+        "-q",
+        "-o",
+        path_str,
+    ]);
+    assert!(output.status.success());
+    assert_eq!("#shift:11\nSpwwz Hzcwo", fs::read_to_string(&path).unwrap());
+
+    let output = run(&["decrypt", "-f", path_str]);
+    assert!(output.status.success());
+    assert_eq!("Hello World\n", String::from_utf8_lossy(&output.stdout));
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_cli_analyze_stats_json() {
+    use caesar_cipher_enc_dec::caesar_cipher::FrequencyStats;
+
+    let output = run(&["analyze", "--text", "Hello, World!", "--stats-json"]);
+    assert!(output.status.success());
+
+    let stats: FrequencyStats = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats.total_letters, 10);
+    assert_eq!(stats.counts.len(), 26);
+    assert_eq!(stats.counts[&'L'], 3);
+}
+
+#[test]
+fn test_interactive_trim_toggle_keeps_spaces() {
+    let input = "e\n  Hello  \n3\nt\ne\n  Hello  \n3\nt\ne\n  Hello  \n3\nq\n";
+    let mut output = Vec::new();
+    interactive_loop(input.as_bytes(), &mut output, None).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Trimming input: off"));
+    assert!(output.contains("Trimming input: on"));
+    assert!(output.contains("Result:   Khoor  \n"));
+    assert_eq!(2, output.matches("Result: Khoor\n").count());
+}
+
+#[test]
+fn test_load_mapping() {
+    let path = temp_path("mapping.txt");
+    fs::write(&path, "qwertyuiop\nasdfghjkl\nzxcvbnm\n").unwrap();
+    let mapping = load_mapping(path.to_str().unwrap()).unwrap();
+    assert_eq!(mapping[0], 'Q');
+    assert_eq!(mapping[25], 'M');
+
+    fs::write(&path, "QWERTYUIOPASDFGHJKLZXCVBN").unwrap();
+    let err = load_mapping(path.to_str().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("found 25"), "{}", err);
+
+    fs::write(&path, "QWERTYUIOPASDFGHJKLZXCVBNQ").unwrap();
+    let err = load_mapping(path.to_str().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("more than once"), "{}", err);
+
+    fs::write(&path, "QWERTYUIOPASDFGHJKLZXCVBN1").unwrap();
+    assert!(load_mapping(path.to_str().unwrap()).is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_substitute() {
+    let path = temp_path("substitute_map.txt");
+    fs::write(&path, "QWERTYUIOPASDFGHJKLZXCVBNM").unwrap();
+    let output = run(&[
+        "substitute",
+        "--text",
+        "Hello, World!",
+        "--map-file",
+        path.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!("Itssg, Vgksr!\n", String::from_utf8_lossy(&output.stdout));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_effective_shift() {
+    let min = i16::MIN.to_string();
+    for (shift, expected) in [("33", "7\n"), ("-1", "25\n"), ("0", "0\n"), (&min, "18\n")] {
+        let output = run(&["effective-shift", "--shift", shift]);
+
+        assert!(output.status.success());
+        assert_eq!(expected, String::from_utf8_lossy(&output.stdout));
+    }
+}
+
+#[test]
+fn test_cli_diff_marks_changed_chars() {
+    let output = run(&["diff", "--text", "Hi, you!", "--shift", "3"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        "Hi, you!\nKl, brx!\n^^  ^^^\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+/// keeps the message of every log record, for checking the debug logging.
+struct CapturingLogger(Mutex<Vec<String>>);
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+    fn log(&self, record: &log::Record) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+#[test]
+fn test_run_cli_logs_debug_records() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+    let path = temp_path("logged.txt");
+    let cli = Cli::parse_from([
+        "caesar_cipher_enc_dec",
+        "encrypt",
+        "-t",
+        "Hello",
+        "-q",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    run_cli(cli.command.unwrap()).unwrap();
+
+    let records = LOGGER.0.lock().unwrap();
+    assert!(records.contains(&"running the encrypt command".to_string()));
+    assert!(records.contains(&"reading the input from --text".to_string()));
+    assert!(records.iter().any(|r| r.contains(path.to_str().unwrap())));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_default_shift_from_env() {
+    let output = Command::new(env!("CARGO_BIN_EXE_caesar_cipher_enc_dec"))
+        .args(["encrypt", "-t", "Hello"])
+        .env("CAESAR_DEFAULT_SHIFT", "1")
+        .output()
+        .expect("failed to run the binary");
+
+    assert!(output.status.success());
+    assert_eq!("Ifmmp\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_cli_armor_roundtrip() {
+    let path = temp_path("armored.txt");
+    let path_str = path.to_str().unwrap();
+    let output = run(&[
+        "encrypt", "-t", "Hello", "-s", "5", "--armor", "-q", "-o", path_str,
+    ]);
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&path).unwrap().contains("shift: 5"));
+
+    let output = run(&["decrypt", "-f", path_str]);
+    assert!(output.status.success());
+    assert_eq!("Hello\n", String::from_utf8_lossy(&output.stdout));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_brute_force_output_to_file() {
+    let path = temp_path("brute_force.txt");
+    let output = run(&[
+        "brute-force",
+        "--text",
+        "Khoor",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+
+    let table = fs::read_to_string(&path).unwrap();
+    assert_eq!(26, table.lines().count());
+    assert_eq!(Some("Shift  0: Khoor"), table.lines().next());
+    assert!(table.lines().any(|line| line == "Shift  3: Hello"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_find_shift() {
+    let output = run(&["find-shift", "--plain", "Hello", "--cipher", "Khoor"]);
+    assert!(output.status.success());
+    assert_eq!("3\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = run(&["find-shift", "--plain", "Hello", "--cipher", "World"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not related"));
+}
+
+#[test]
+fn test_cli_random_shift_with_seed_is_reproducible() {
+    let args = ["encrypt", "-t", "Hello", "--random-shift", "--seed", "42"];
+    let first = run(&args);
+    let second = run(&args);
+
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+    assert_eq!(first.stderr, second.stderr);
+}
+
+#[test]
+fn test_cli_lowercase() {
+    let output = run(&["encrypt", "-t", "Hello World", "-s", "3", "--lowercase"]);
+
+    assert!(output.status.success());
+    assert_eq!("khoor zruog\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_cli_warns_about_non_latin_letters() {
+    let output = run(&["encrypt", "-t", "café", "-s", "1"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning"));
+
+    let output = run(&["encrypt", "-t", "café", "-s", "1", "--quiet"]);
+    assert!(output.stderr.is_empty());
+    assert_eq!("dbgé\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_interactive_history_logs_operations() {
+    let path = temp_path("history.txt");
+    let history = fs::File::create(&path).unwrap();
+    let input = "e\nHello World\n3\nx\nd\nKhoor\n\nq\n";
+    interactive_loop(input.as_bytes(), Vec::new(), Some(history)).unwrap();
+
+    let history = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        history.lines().collect::<Vec<_>>(),
+        ["encrypt shift=3 len=11", "decrypt shift=3 len=5"]
+    );
+    assert!(!history.contains("Hello"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_parse_shift() {
+    assert_eq!(parse_shift("-4"), Ok(-4));
+    assert_eq!(
+        parse_shift("abc").unwrap_err().to_string(),
+        "invalid shift: 'abc' is not a valid integer shift"
+    );
+    let Err(err) = Cli::try_parse_from(["caesar", "encrypt", "--text", "hi", "--shift", "abc"])
+    else {
+        panic!("'abc' should not parse as a shift");
+    };
+    let err = err.to_string();
+    assert!(
+        err.contains("'abc' is not a valid integer shift"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_cli_text_env() {
+    // the variable is only set for the child process
+    let output = Command::new(env!("CARGO_BIN_EXE_caesar_cipher_enc_dec"))
+        .args([
+            "encrypt",
+            "--text-env",
+            "CAESAR_TEST_SECRET",
+            "--shift",
+            "3",
+        ])
+        .env("CAESAR_TEST_SECRET", "Hello World")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Khoor Zruog"
+    );
+
+    let output = run(&[
+        "encrypt",
+        "--text",
+        "hi",
+        "--text-env",
+        "CAESAR_TEST_SECRET",
+    ]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_read_env_text_unset() {
+    let err = read_env_text("CAESAR_TEST_SURELY_UNSET").unwrap_err();
+    assert!(
+        err.to_string().contains("$CAESAR_TEST_SURELY_UNSET"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_cli_decrypt_shift_from_file() {
+    let path = temp_path("keyed.txt");
+    fs::write(&path, "3\nKhoor\n").unwrap();
+    let output = run(&[
+        "decrypt",
+        "--file",
+        path.to_str().unwrap(),
+        "--shift-from-file",
+    ]);
+    fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+    assert_eq!("Hello\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_split_shift_line() {
+    assert_eq!(
+        split_shift_line("3\r\nKhoor\nZruog"),
+        Ok((3, "Khoor\nZruog".to_string()))
+    );
+    assert_eq!(
+        split_shift_line("3\nKhoor\r\n\n"),
+        Ok((3, "Khoor\r\n".to_string()))
+    );
+    assert_eq!(
+        split_shift_line("Khoor\n").unwrap_err().to_string(),
+        "invalid shift: 'Khoor' is not a valid integer shift"
+    );
+}
+
+#[test]
+fn test_tabulate() {
+    let entries: Vec<String> = ["a", "bb", "c", "d", "e", "f"].map(String::from).to_vec();
+    assert_eq!(tabulate(&entries, 4), "a   bb  c   d\ne   f\n");
+    assert_eq!(tabulate(&entries[..2], 0), "a\nbb\n");
+    assert_eq!(tabulate(&[], 4), "");
+}
+
+#[test]
+fn test_cli_strict_ascii() {
+    let output = run(&[
+        "encrypt",
+        "--text",
+        "Hello",
+        "--shift",
+        "3",
+        "--strict-ascii",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Khoor");
+
+    let output = run(&["encrypt", "--text", "caf\u{e9}", "--strict-ascii"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'\u{e9}'"));
+}
+
+fn brute_candidates() -> Vec<(i16, String)> {
+    vec![(0, "Khoor, there".to_string()), (3, "Hello".to_string())]
+}
+
+#[test]
+fn test_format_brute_lines() {
+    assert_eq!(
+        format_brute_lines(&brute_candidates()),
+        "Shift  0: Khoor, there\nShift  3: Hello"
+    );
+}
+
+#[test]
+fn test_format_brute_table() {
+    assert_eq!(
+        format_brute_table(&brute_candidates()),
+        "Shift  Text\n    0  Khoor, there\n    3  Hello"
+    );
+}
+
+#[test]
+fn test_format_brute_csv() {
+    assert_eq!(
+        format_brute_csv(&brute_candidates()),
+        "shift,text\n0,\"Khoor, there\"\n3,Hello"
+    );
+}
+
+#[test]
+fn test_cli_default_shift_env_applies_to_other_commands() {
+    let cases: [(&[&str], &str); 2] = [
+        (&["diff", "-t", "ab"], "ab\nbc\n^^\n"),
+        (&["compare", "-t", "ab"], "a→b\nb→c\n"),
+    ];
+    for (args, expected) in cases {
+        let output = Command::new(env!("CARGO_BIN_EXE_caesar_cipher_enc_dec"))
+            .args(args)
+            .env("CAESAR_DEFAULT_SHIFT", "1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(expected, String::from_utf8_lossy(&output.stdout));
+    }
+}
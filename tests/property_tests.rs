@@ -0,0 +1,14 @@
+use caesar_cipher_enc_dec::caesar_cipher::{decrypt, encrypt};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn encrypt_then_decrypt_is_identity(text in any::<String>(), shift in -25i16..=25) {
+        prop_assert_eq!(decrypt(&encrypt(&text, shift), shift), text);
+    }
+
+    #[test]
+    fn shift_zero_is_identity(text in any::<String>()) {
+        prop_assert_eq!(encrypt(&text, 0), text);
+    }
+}
@@ -1,12 +1,32 @@
 #[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
 
-mod tests{
-    use caesar_cipher_enc_dec::caesar_cipher::{decrypt, encrypt};
+    use caesar_cipher_enc_dec::a1z26;
+    use caesar_cipher_enc_dec::alphabet::{encrypt_with_alphabets, Alphabet};
+    use caesar_cipher_enc_dec::caesar_cipher::{
+        are_caesar_related, armor, best_shift, best_shift_multi, best_shift_with_dictionary,
+        change_mask, cheat_sheet, chi_squared, cipher_table, constant_time_eq,
+        contains_non_latin_letters, crack, crack_summary, crack_with_confidence, dearmor, decrypt,
+        decrypt_all, decrypt_best, decrypt_cheat_sheet, decrypt_safe, encrypt,
+        encrypt_bytes_in_place, encrypt_default, encrypt_detailed, encrypt_except,
+        encrypt_filtered, encrypt_lines, encrypt_lines_cascading, encrypt_lower, encrypt_owned,
+        encrypt_prefix, encrypt_safe_bounded, encrypt_stream_with_progress, encrypt_stripped,
+        encrypt_to_bytes, encrypt_twice, encrypt_upper, encrypt_with_key_sequence,
+        encrypt_with_stats, ensure_ascii, frequency_chart, frequency_stats, full_tabula_recta,
+        group_letters, index_of_coincidence, is_probably_english, letter_frequencies,
+        likely_key_lengths, net_shift, normalize_shift, random_shift, random_shift_seeded,
+        safe_matches_unsafe, score_by_dictionary, scored_candidates, shift_char, shift_from_letter,
+        shift_from_passphrase, shifts_matching, side_by_side, substitute, transform,
+        unaffected_chars, wrap_text, Caesar, Cipher, CipherBuilder, CipherError, Direction,
+        EncryptWriter, Encryptor,
+    };
+    use caesar_cipher_enc_dec::config::{default_shift_from_env, DEFAULT_SHIFT, DEFAULT_SHIFT_ENV};
     #[test]
     fn encrypt_text() {
         let text: &str = "I LOVE YOU.";
-        let enc_text: String = encrypt(&text, 3);
-        let dec_text:String = encrypt(&enc_text, -3);
+        let enc_text: String = encrypt(text, 3);
+        let dec_text: String = encrypt(&enc_text, -3);
         println!("enc_test:{}", enc_text);
         println!("dec_text:{}", dec_text);
 
@@ -14,11 +34,775 @@ mod tests{
         assert_eq!("L ORYH BRX.", enc_text)
     }
     #[test]
-    fn decrypt_text(){
+    fn decrypt_text() {
         let text: &str = "L ORYH BRX.";
-        let dec_text = decrypt(&text, 3);
+        let dec_text = decrypt(text, 3);
 
         assert_eq!("I LOVE YOU.", dec_text)
     }
+    #[test]
+    fn crack_recovers_shift() {
+        let enc_text = encrypt("The quick brown fox", 7);
+        let (shift, dec_text) = crack(&enc_text);
+
+        assert_eq!(7, shift);
+        assert_eq!("The quick brown fox", dec_text);
+    }
+    #[test]
+    fn crack_without_letters() {
+        assert_eq!((0, String::new()), crack(""));
+        assert_eq!((0, "123 !?".to_string()), crack("123 !?"));
+    }
+    #[test]
+    fn crack_summary_reports_shift() {
+        let enc_text = encrypt("It was the best of times, it was the worst of times", 11);
+        let summary = crack_summary(&enc_text);
+
+        assert!(summary.contains("Guessed shift: 11"));
+        assert_eq!(
+            3,
+            summary
+                .lines()
+                .filter(|l| l.contains("shift") && l.contains("score"))
+                .count()
+        );
+    }
+    #[test]
+    fn safe_matches_unsafe_valid() {
+        assert!(safe_matches_unsafe("Hello, World!", 3));
+        assert!(safe_matches_unsafe("Hello, World!", -25));
+    }
+    #[test]
+    fn safe_matches_unsafe_empty_text() {
+        assert!(safe_matches_unsafe("", 3));
+    }
+    #[test]
+    fn shift_from_letter_key() {
+        assert_eq!(Ok(0), shift_from_letter('A'));
+        assert_eq!(Ok(3), shift_from_letter('D'));
+        assert_eq!(Ok(3), shift_from_letter('d'));
+        assert!(shift_from_letter('7').is_err());
+    }
+    #[test]
+    fn decrypt_cheat_sheet_lines() {
+        let sheet = decrypt_cheat_sheet(3);
+
+        assert!(sheet.contains("D -> A"));
+        assert!(sheet.contains("A -> X"));
+        assert_eq!(26, sheet.lines().count());
+        assert!(cheat_sheet(3).contains("A -> D"));
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn cipher_error_is_std_error() {
+        let error: Box<dyn std::error::Error> = Box::new(CipherError::EmptyText);
+
+        assert_eq!("text is empty", error.to_string());
+    }
+    #[test]
+    fn encrypt_upper_folds_case() {
+        assert_eq!("KHOOR", encrypt_upper("Hello", 3));
+        assert_eq!("KHOOR, ZRUOG!", encrypt_upper("hello, World!", 3));
+        // case is lost, so decrypting does not give back the input
+        assert_eq!("HELLO", decrypt(&encrypt_upper("Hello", 3), 3));
+    }
+    #[test]
+    fn net_shift_of_chain() {
+        assert_eq!(6, net_shift(&[3, 5, -2]));
+        assert_eq!(0, net_shift(&[]));
+        assert_eq!(25, net_shift(&[i16::MIN, i16::MAX]));
+
+        let twice = encrypt(&encrypt("Hello", 3), 5);
+        assert_eq!("Hello", decrypt(&twice, net_shift(&[3, 5])));
+    }
+    #[test]
+    fn encrypt_stripped_drops_non_letters() {
+        assert_eq!("Ijuifsf", encrypt_stripped("Hi, there!", 1));
+        assert_eq!("BCD", encrypt_stripped("A-B_C 123.", 1));
+        assert_eq!("dbgmb", encrypt_stripped("café, 日本 à la", 1));
+        assert_eq!("", encrypt_stripped("!? 42", 5));
+    }
+    #[test]
+    fn group_letters_in_fives() {
+        assert_eq!("IJUIF SF", group_letters("IJUIFSF", 5));
+        assert_eq!("ABCDE FGHIJ", group_letters("ABCDEFGHIJ", 5));
+        assert_eq!("AB CD E", group_letters("ABCDE", 2));
+        assert_eq!("ABCDE", group_letters("ABCDE", 0));
+        assert_eq!("", group_letters("", 5));
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn cipher_error_serde_roundtrip() {
+        let errors = [
+            CipherError::EmptyText,
+            CipherError::InvalidShift("30 is outside -25..=25".to_string()),
+            CipherError::InvalidEncoding("invalid Base64".to_string()),
+            CipherError::Io("failed to read notes.txt".to_string()),
+            CipherError::TooLong { len: 12, max: 10 },
+            CipherError::NonAscii('\u{e9}'),
+        ];
+        for error in errors {
+            let json = serde_json::to_string(&error).unwrap();
+            let back: CipherError = serde_json::from_str(&json).unwrap();
+            assert_eq!(error, back);
+        }
+        assert_eq!(
+            r#"{"InvalidShift":"bad"}"#,
+            serde_json::to_string(&CipherError::InvalidShift("bad".to_string())).unwrap()
+        );
+    }
+    #[test]
+    fn cipher_builder_options() {
+        let text = "Hello, World 42!";
+        assert_eq!(
+            encrypt(text, 3),
+            CipherBuilder::new().shift(3).encrypt(text)
+        );
+
+        let upper_stripped = CipherBuilder::new()
+            .shift(3)
+            .preserve_case(false)
+            .strip_non_alpha(true);
+        assert_eq!("KHOORZRUOG", upper_stripped.encrypt(text));
+
+        let digits = CipherBuilder::new().shift(3).shift_digits(true);
+        assert_eq!("Khoor, Zruog 75!", digits.encrypt(text));
+        assert_eq!(text, digits.decrypt(&digits.encrypt(text)));
+    }
+    #[test]
+    fn caesar_cipher_trait() {
+        let caesar = Caesar { shift: 3 };
+        let enc_text = Cipher::encrypt(&caesar, "I LOVE YOU.").unwrap();
+
+        assert_eq!("L ORYH BRX.", enc_text);
+        assert_eq!("I LOVE YOU.", Cipher::decrypt(&caesar, &enc_text).unwrap());
+        assert_eq!(Err(CipherError::EmptyText), Cipher::encrypt(&caesar, ""));
+    }
+    #[test]
+    fn normalize_shift_range() {
+        assert_eq!(3, normalize_shift(29));
+        assert_eq!(25, normalize_shift(-1));
+        assert_eq!(0, normalize_shift(-26));
+    }
+    #[test]
+    fn encrypt_with_stats_counts_letters() {
+        assert_eq!(("bc!".to_string(), 2), encrypt_with_stats("ab!", 1));
+        assert_eq!((String::new(), 0), encrypt_with_stats("", 1));
+        assert_eq!(("123 é".to_string(), 0), encrypt_with_stats("123 é", 1));
+        assert_eq!(
+            encrypt("Hello, World!", 3),
+            encrypt_with_stats("Hello, World!", 3).0
+        );
+    }
+    #[test]
+    fn shifts_matching_target() {
+        assert_eq!(vec![3], shifts_matching("Khoor", "hello"));
+        assert_eq!(Vec::<i16>::new(), shifts_matching("Khoor", "world"));
+        assert_eq!((0..26).collect::<Vec<i16>>(), shifts_matching("123", "123"));
+    }
+    #[test]
+    fn encrypt_lines_cascading_three_lines() {
+        let text = "abc\r\nabc\nabc";
+
+        assert_eq!("def\r\nfgh\nhij", encrypt_lines_cascading(text, 3, 2));
+        assert_eq!("abc\r\nabc\nabc", encrypt_lines_cascading(text, 0, 0));
+        assert_eq!("", encrypt_lines_cascading("", 3, 1));
+    }
+    #[test]
+    fn encrypt_detailed_fields() {
+        let result = encrypt_detailed("I LOVE YOU.", 29);
+
+        assert_eq!("L ORYH BRX.", result.ciphertext);
+        assert_eq!(3, result.effective_shift);
+        assert_eq!(8, result.letters_shifted);
+    }
+    #[test]
+    fn alphabet_index_and_wrap() {
+        let upper = Alphabet::ascii_upper();
+        assert_eq!(Some(0), upper.index_of('A'));
+        assert_eq!(Some(25), upper.index_of('Z'));
+        assert_eq!(None, upper.index_of('a'));
+        assert_eq!('A', upper.shift('Z', 1));
+        assert_eq!('z', Alphabet::ascii_lower().shift('a', -1));
+        assert_eq!('!', upper.shift('!', 3));
+
+        let digits = Alphabet::custom(&['0', '1', '2']).unwrap();
+        assert_eq!('1', digits.shift('2', 2));
+        assert_eq!('1', digits.shift('0', i16::MIN));
+    }
+    #[test]
+    fn custom_alphabet_rejects_duplicates() {
+        assert_eq!(
+            Alphabet::custom(&['a', 'b', 'a']),
+            Err(CipherError::InvalidEncoding(
+                "'a' appears more than once in the alphabet".to_string()
+            ))
+        );
+    }
+    #[test]
+    fn encrypt_with_latin_alphabets() {
+        let latin = [Alphabet::ascii_upper(), Alphabet::ascii_lower()];
+        let text = "Hello, World! é";
+
+        assert_eq!(encrypt(text, 3), encrypt_with_alphabets(text, 3, &latin));
+        assert_eq!(
+            encrypt(text, -29),
+            encrypt_with_alphabets(text, -29, &latin)
+        );
+    }
+    #[test]
+    fn decrypt_best_result() {
+        assert_eq!(Err(CipherError::EmptyText), decrypt_best(""));
+        assert_eq!(Err(CipherError::EmptyText), decrypt_best(" \n\t"));
+
+        let enc_text = encrypt("Meet me by the river at noon", 17);
+        assert_eq!(
+            Ok((17, "Meet me by the river at noon".to_string())),
+            decrypt_best(&enc_text)
+        );
+    }
+    #[test]
+    fn a1z26_roundtrip() {
+        assert_eq!("8 5 12 12 15", a1z26::encode("hello"));
+        assert_eq!("8 9 26", a1z26::encode("Hi, Z!"));
+        assert_eq!(Ok("HELLO".to_string()), a1z26::decode("8 5 12 12 15"));
+        assert_eq!(
+            Ok("HIZ".to_string()),
+            a1z26::decode(&a1z26::encode("Hi, Z!"))
+        );
+        assert!(a1z26::decode("0").is_err());
+        assert!(a1z26::decode("27").is_err());
+        assert!(a1z26::decode("8 x").is_err());
+        assert!(a1z26::decode("+5").is_err());
+    }
+    #[test]
+    fn constant_time_eq_compare() {
+        assert!(constant_time_eq("Hello", "Hello"));
+        assert!(constant_time_eq("", ""));
+        assert!(!constant_time_eq("Hello", "Hellp"));
+        assert!(!constant_time_eq("Hello", "Hello!"));
+        assert!(!constant_time_eq("Hello\0", "Hello"));
+    }
+    #[test]
+    fn wrap_text_at_ten() {
+        let text = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let wrapped = wrap_text(text, 10);
+
+        assert_eq!("ABCDEFGHIJ\nKLMNOPQRST\nUVWXYZ", wrapped);
+        assert!(wrapped.lines().all(|line| line.chars().count() <= 10));
+        assert_eq!("AB\nC\nDE\nF", wrap_text("ABC\nDEF", 2));
+        assert_eq!(text, wrap_text(text, 0));
+    }
+    #[test]
+    fn cipher_table_rows() {
+        let (plain, cipher) = cipher_table(3);
 
+        assert_eq!("ABCDEFGHIJKLMNOPQRSTUVWXYZ", plain);
+        assert_eq!("DEFGHIJKLMNOPQRSTUVWXYZABC", cipher);
+        assert_eq!("ZABCDEFGHIJKLMNOPQRSTUVWXY", cipher_table(-1).1);
+    }
+    #[test]
+    fn encrypt_with_key_sequence_cycles() {
+        assert_eq!("BCDBCD", encrypt_with_key_sequence("AAAAAA", &[1, 2, 3]));
+        assert_eq!(
+            "BC-D bcd",
+            encrypt_with_key_sequence("AA-A aaa", &[1, 2, 3])
+        );
+        assert_eq!("AAAAAA", encrypt_with_key_sequence("AAAAAA", &[]));
+        assert_eq!(
+            encrypt("Hello", 5),
+            encrypt_with_key_sequence("Hello", &[5])
+        );
+    }
+    #[test]
+    fn transform_directions() {
+        assert_eq!(decrypt("ABC", 3), transform("ABC", 3, Direction::Backward));
+        assert_eq!(encrypt("ABC", 3), transform("ABC", 3, Direction::Forward));
+        assert_eq!("XYZ", transform("ABC", 3, Direction::Backward));
+        assert_eq!(encrypt("ABC", 8), decrypt("ABC", i16::MIN));
+        assert!(decrypt_safe("ABC", i16::MIN).is_err());
+        assert_eq!("ABC", CipherBuilder::new().shift(i16::MIN).decrypt("STU"));
+    }
+    #[test]
+    fn frequency_chart_bars() {
+        let freqs = letter_frequencies("AAAAB");
+        assert_eq!(0.8, freqs[0]);
+        assert_eq!(0.2, freqs[1]);
+
+        let chart = frequency_chart(&freqs, 8);
+        assert_eq!(26, chart.lines().count());
+        assert!(chart.starts_with("A |########\nB |##\nC |\n"));
+
+        let empty = frequency_chart(&letter_frequencies("123"), 8);
+        assert!(empty.lines().all(|line| line.ends_with('|')));
+    }
+    #[test]
+    fn score_by_dictionary_matches() {
+        let words: HashSet<String> = ["hello", "world"].iter().map(|w| w.to_string()).collect();
+
+        assert_eq!(2, score_by_dictionary("Hello, WORLD! again", &words));
+        assert_eq!(0, score_by_dictionary("Khoor Zruog", &words));
+        assert_eq!(3, best_shift_with_dictionary("Khoor Zruog", &words));
+    }
+    #[test]
+    fn encryptor_reuses_buffer() {
+        let mut encryptor = Encryptor::new(3);
+        let lines = ["I LOVE YOU.", "hello", "", "Zebra!"];
+        for line in lines {
+            assert_eq!(encrypt(line, 3), encryptor.feed(line));
+        }
+
+        let long_line = "x".repeat(1000);
+        encryptor.feed(&long_line);
+        let capacity = encryptor.capacity();
+        for _ in 0..100 {
+            encryptor.feed(&long_line);
+            encryptor.feed("short");
+        }
+        assert_eq!(capacity, encryptor.capacity());
+    }
+    #[test]
+    fn random_shift_in_range() {
+        for _ in 0..1000 {
+            assert!((1..=25).contains(&random_shift()));
+        }
+    }
+    #[test]
+    fn are_caesar_related_finds_shift() {
+        assert_eq!(are_caesar_related("ABC", "DEF"), Some(3));
+        assert_eq!(
+            are_caesar_related("Hello, World!", "Khoor, Zruog!"),
+            Some(3)
+        );
+        assert_eq!(are_caesar_related("XYZ", "ABC"), Some(3));
+        assert_eq!(are_caesar_related("ABC", "DEG"), None);
+        assert_eq!(are_caesar_related("ABC", "def"), None);
+        assert_eq!(are_caesar_related("A B", "D-E"), None);
+        assert_eq!(are_caesar_related("ABC", "DE"), None);
+    }
+    #[test]
+    fn encrypt_filtered_by_case() {
+        let text = "Hello, World!";
+        assert_eq!(encrypt_filtered(text, 3, true, true), encrypt(text, 3));
+        assert_eq!(encrypt_filtered(text, 3, true, false), "Kello, Zorld!");
+        assert_eq!(encrypt_filtered(text, 3, false, true), "Hhoor, Wruog!");
+        assert_eq!(encrypt_filtered(text, 3, false, false), text);
+    }
+    #[test]
+    fn shift_from_passphrase_sums_letters() {
+        assert_eq!(
+            shift_from_passphrase("secret"),
+            shift_from_passphrase("secret")
+        );
+        assert_eq!(
+            shift_from_passphrase("Secret!"),
+            shift_from_passphrase("sECRET")
+        );
+        // 18 + 4 + 2 + 17 + 4 + 19 = 64
+        assert_eq!(shift_from_passphrase("secret"), 12);
+        assert_eq!(shift_from_passphrase(""), 0);
+        assert_eq!(shift_from_passphrase("1234 !?"), 0);
+    }
+    #[test]
+    fn cipher_error_from_io_error() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = CipherError::from(io_err);
+
+        assert_eq!(err, CipherError::Io("no such file".to_string()));
+        assert_eq!(err.to_string(), "i/o error: no such file");
+    }
+    #[test]
+    fn index_of_coincidence_of_english() {
+        let english = "It was the best of times, it was the worst of times, \
+            it was the age of wisdom, it was the age of foolishness, \
+            it was the epoch of belief, it was the epoch of incredulity";
+        let ic = index_of_coincidence(english);
+        assert!((0.055..0.08).contains(&ic), "english ic {}", ic);
+
+        let random = "QWERTYUIOPASDFGHJKLZXCVBNMMNBVCXZLKJHGFDSAPOIUYTREWQ";
+        let ic = index_of_coincidence(random);
+        assert!(ic < 0.045, "random ic {}", ic);
+
+        assert_eq!(index_of_coincidence("AA"), 1.0);
+        assert_eq!(index_of_coincidence("A"), 0.0);
+        assert_eq!(index_of_coincidence("123"), 0.0);
+    }
+    #[test]
+    fn likely_key_lengths_finds_key_length() {
+        let plain_text = "Frequency analysis is based on the fact that, in any given \
+            stretch of written language, certain letters and combinations of letters \
+            occur with varying frequencies. Moreover, there is a characteristic \
+            distribution of letters that is roughly the same for almost all samples \
+            of that language. In English the letters E, T, A and O are the most \
+            common, while Z, Q, X and J are rare.";
+        let enc_text = encrypt_with_key_sequence(plain_text, &[7, 4, 11, 15]);
+        let lengths = likely_key_lengths(&enc_text, 6);
+
+        assert_eq!(lengths.len(), 6);
+        assert_eq!(lengths[0].0, 4, "{:?}", lengths);
+    }
+    #[test]
+    fn shift_char_wraps_and_passes_through() {
+        assert_eq!(shift_char('Z', 1), 'A');
+        assert_eq!(shift_char('a', -1), 'z');
+        assert_eq!(shift_char('5', 3), '5');
+        assert_eq!(shift_char('é', 3), 'é');
+        let text = "Hello, World!";
+        let shifted: String = text.chars().map(|c| shift_char(c, 7)).collect();
+        assert_eq!(shifted, encrypt(text, 7));
+    }
+    #[test]
+    fn encrypt_stream_reports_progress() {
+        let text = "Hello, wörld! ".repeat(1500);
+        let mut out = Vec::new();
+        let mut progress = Vec::new();
+        encrypt_stream_with_progress(text.as_bytes(), &mut out, 3, |done| progress.push(done))
+            .unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), encrypt(&text, 3));
+        assert!(progress.len() > 1);
+        assert!(progress.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(*progress.last().unwrap(), text.len() as u64);
+    }
+    #[test]
+    fn is_probably_english_detects_plain_text() {
+        let plain_text = "Frequency analysis is based on the fact that, in any given stretch \
+            of written language, certain letters occur with varying frequencies.";
+        assert!(is_probably_english(plain_text));
+        assert!(!is_probably_english(&encrypt(plain_text, 5)));
+        assert!(!is_probably_english("QWERTYUIOPASDFGHJKLZXCVBNM"));
+        assert!(!is_probably_english(""));
+    }
+    #[test]
+    fn decrypt_all_keyed_by_shift() {
+        let all = decrypt_all("Khoor, Zruog!");
+
+        assert_eq!(all.len(), 26);
+        assert_eq!(all[&0], "Khoor, Zruog!");
+        assert_eq!(all[&3], "Hello, World!");
+        assert!(all.keys().copied().eq(0..26));
+    }
+    #[test]
+    fn encrypt_safe_bounded_counts_chars() {
+        assert_eq!(encrypt_safe_bounded("Hello", 3, 5), Ok("Khoor".to_string()));
+        assert_eq!(encrypt_safe_bounded("héllo", 3, 5), Ok("kéoor".to_string()));
+
+        let err = encrypt_safe_bounded("Hello!", 3, 5).unwrap_err();
+        assert_eq!(err, CipherError::TooLong { len: 6, max: 5 });
+        let message = err.to_string();
+        assert!(
+            message.contains('6') && message.contains('5'),
+            "{}",
+            message
+        );
+
+        assert_eq!(encrypt_safe_bounded("", 3, 5), Err(CipherError::EmptyText));
+        assert!(matches!(
+            encrypt_safe_bounded("Hello", 30, 5),
+            Err(CipherError::InvalidShift(_))
+        ));
+    }
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn brute_force_parallel_matches_sequential() {
+        use caesar_cipher_enc_dec::caesar_cipher::{brute_force, brute_force_parallel};
+
+        let text = encrypt("The quick brown fox jumps over the lazy dog. ", 7).repeat(2000);
+        assert_eq!(brute_force_parallel(&text), brute_force(&text));
+    }
+    #[test]
+    fn encrypt_bytes_in_place_matches_encrypt() {
+        let text = "Hello, World! xyz";
+        let mut buf = text.as_bytes().to_vec();
+        encrypt_bytes_in_place(&mut buf, 3);
+        assert_eq!(buf, encrypt(text, 3).as_bytes());
+
+        let mut buf = "é".as_bytes().to_vec();
+        assert_eq!(buf[0], 0xC3);
+        encrypt_bytes_in_place(&mut buf, 3);
+        assert_eq!(buf, "é".as_bytes());
+    }
+    #[test]
+    fn frequency_stats_counts_letters() {
+        let stats = frequency_stats("Hello, World!");
+
+        assert_eq!(stats.total_letters, 10);
+        assert_eq!(stats.counts.len(), 26);
+        assert_eq!(stats.counts[&'L'], 3);
+        assert_eq!(stats.counts[&'Z'], 0);
+        assert_eq!(stats.ioc, index_of_coincidence("Hello, World!"));
+    }
+    #[test]
+    fn substitute_matches_caesar_table() {
+        let mut mapping = ['A'; 26];
+        for (entry, c) in mapping.iter_mut().zip(cipher_table(3).1.chars()) {
+            *entry = c;
+        }
+        let text = "Hello, World!";
+        assert_eq!(substitute(text, &mapping), encrypt(text, 3));
+    }
+    #[test]
+    fn unaffected_chars_in_order() {
+        assert_eq!(unaffected_chars("a1!a1"), vec!['1', '!']);
+        assert_eq!(unaffected_chars("Hello"), Vec::<char>::new());
+        assert_eq!(unaffected_chars("ß é ß"), vec!['ß', ' ', 'é']);
+    }
+    #[test]
+    fn change_mask_marks_shifted_chars() {
+        assert_eq!(change_mask("ab!", 0), vec![false, false, false]);
+        assert_eq!(change_mask("ab!", 1), vec![true, true, false]);
+        assert_eq!(change_mask("ab!", 52), vec![false, false, false]);
+        assert_eq!(change_mask("", 3), Vec::<bool>::new());
+    }
+    // the only test that touches CAESAR_DEFAULT_SHIFT, so the cases run in order
+    #[test]
+    fn default_shift_from_env_var() {
+        std::env::remove_var(DEFAULT_SHIFT_ENV);
+        assert_eq!(default_shift_from_env(), DEFAULT_SHIFT);
+
+        std::env::set_var(DEFAULT_SHIFT_ENV, "7");
+        assert_eq!(default_shift_from_env(), 7);
+        std::env::set_var(DEFAULT_SHIFT_ENV, "-1");
+        assert_eq!(default_shift_from_env(), 25);
+        std::env::set_var(DEFAULT_SHIFT_ENV, "seven");
+        assert_eq!(default_shift_from_env(), DEFAULT_SHIFT);
+
+        std::env::remove_var(DEFAULT_SHIFT_ENV);
+    }
+    #[test]
+    fn encrypt_except_keeps_words() {
+        assert_eq!(encrypt_except("Hello World", 3, &["World"]), "Khoor World");
+        assert_eq!(encrypt_except("Hello World", 3, &["WORLD"]), "Khoor World");
+        assert_eq!(
+            encrypt_except("  Alice met\tBob\n", 1, &["alice", "bob"]),
+            "  Alice nfu\tBob\n"
+        );
+        assert_eq!(
+            encrypt_except("Hello World!", 3, &["World"]),
+            "Khoor Zruog!"
+        );
+        assert_eq!(encrypt_except("Hello", 3, &[]), encrypt("Hello", 3));
+        assert_eq!(encrypt_except("", 3, &["a"]), "");
+    }
+    #[test]
+    fn armor_roundtrip() {
+        let block = armor("Khoor, Zruog!", 3);
+        assert_eq!(
+            block,
+            "-----BEGIN CAESAR-----\nshift: 3\n\nKhoor, Zruog!\n-----END CAESAR-----"
+        );
+        assert_eq!(dearmor(&block), Ok((3, "Khoor, Zruog!".to_string())));
+        assert_eq!(
+            dearmor(&format!("\n{}\n", block)),
+            Ok((3, "Khoor, Zruog!".to_string()))
+        );
+        assert_eq!(
+            dearmor(&armor("a\n\nb", 25)),
+            Ok((25, "a\n\nb".to_string()))
+        );
+        assert_eq!(dearmor(&armor("", 0)), Ok((0, String::new())));
+    }
+    #[test]
+    fn dearmor_rejects_malformed_blocks() {
+        let missing_end = "-----BEGIN CAESAR-----\nshift: 3\n\nKhoor";
+        assert!(
+            matches!(dearmor(missing_end), Err(CipherError::InvalidEncoding(msg)) if msg.contains("END"))
+        );
+        assert!(dearmor("Khoor\n-----END CAESAR-----").is_err());
+        assert!(dearmor("-----BEGIN CAESAR-----\n\nKhoor\n-----END CAESAR-----").is_err());
+        assert!(matches!(
+            dearmor("-----BEGIN CAESAR-----\nshift: x\n\nKhoor\n-----END CAESAR-----"),
+            Err(CipherError::InvalidShift(_))
+        ));
+    }
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn encrypt_unicode_normalize() {
+        use caesar_cipher_enc_dec::caesar_cipher::encrypt_unicode_normalize;
+
+        // é shifts as e and keeps its accent; f has no precomposed accented form
+        assert_eq!(encrypt_unicode_normalize("café", 1), "dbgf\u{301}");
+        assert_eq!(encrypt_unicode_normalize("Él", 4), "Íp");
+        assert_eq!(encrypt_unicode_normalize("日本 ß", 3), "日本 ß");
+        assert_eq!(encrypt_unicode_normalize("Hello", 3), encrypt("Hello", 3));
+    }
+    #[test]
+    fn encrypt_owned_reuses_buffer() {
+        let text = String::from("Hello, wörld! 日本");
+        assert_eq!(encrypt_owned(text.clone(), 3), encrypt(&text, 3));
+
+        let mut text = String::with_capacity(64);
+        text.push_str("Hello");
+        let ptr = text.as_ptr();
+        let enc_text = encrypt_owned(text, 3);
+        assert_eq!(enc_text, "Khoor");
+        assert_eq!(enc_text.as_ptr(), ptr);
+        assert_eq!(enc_text.capacity(), 64);
+    }
+    #[test]
+    fn encrypt_twice_is_one_caesar() {
+        let text = "The quick brown fox jumps over the lazy dog!";
+        for (a, b) in [
+            (0, 0),
+            (3, 5),
+            (13, 13),
+            (25, 1),
+            (-3, 7),
+            (-20, -20),
+            (100, -1000),
+        ] {
+            assert_eq!(encrypt_twice(text, a, b), encrypt(text, a + b));
+            assert_eq!(encrypt_twice(text, a, b), encrypt(&encrypt(text, a), b));
+        }
+        assert_eq!(
+            encrypt_twice(text, i16::MAX, i16::MAX),
+            encrypt(&encrypt(text, i16::MAX), i16::MAX)
+        );
+    }
+    #[test]
+    fn random_shift_seeded_is_deterministic() {
+        for seed in [0, 1, 42, u64::MAX] {
+            assert_eq!(random_shift_seeded(seed), random_shift_seeded(seed));
+            assert!((1..=25).contains(&random_shift_seeded(seed)));
+        }
+        let shifts: HashSet<i16> = (0..50).map(random_shift_seeded).collect();
+        assert!(shifts.len() > 1);
+    }
+    #[test]
+    fn encrypt_lower_folds_case() {
+        assert_eq!(encrypt_lower("Hello", 3), "khoor");
+        assert_eq!(encrypt_lower("HELLO, World!", 3), "khoor, zruog!");
+        assert_eq!(
+            CipherBuilder::new()
+                .shift(3)
+                .lowercase(true)
+                .encrypt("Hello"),
+            "khoor"
+        );
+    }
+    #[test]
+    fn contains_non_latin_letters_detects_accents() {
+        assert!(!contains_non_latin_letters("Hello, World! 123"));
+        assert!(!contains_non_latin_letters(""));
+        assert!(contains_non_latin_letters("café"));
+        assert!(contains_non_latin_letters("日本"));
+        assert!(!contains_non_latin_letters("€ → ✓"));
+    }
+    #[test]
+    fn case_folding_leaves_non_ascii_alone() {
+        assert_eq!(encrypt_upper("\u{df}", 1), "\u{df}");
+        assert_eq!(encrypt_lower("\u{1e9e}", 1), "\u{1e9e}");
+        assert_eq!(encrypt_upper("stra\u{df}e", 1), "TUSB\u{df}F");
+    }
+    #[test]
+    fn full_tabula_recta_rows() {
+        let table = full_tabula_recta();
+        assert_eq!(table.lines().count(), 26);
+        assert_eq!(table.lines().next(), Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ"));
+        assert_eq!(table.lines().last(), Some("ZABCDEFGHIJKLMNOPQRSTUVWXY"));
+    }
+    #[test]
+    fn encrypt_to_bytes_matches_encrypt() {
+        for text in ["Hello, World!", "caf\u{e9} \u{1f600} Zz", ""] {
+            for shift in [-27, 0, 3, 25] {
+                assert_eq!(
+                    encrypt_to_bytes(text, shift),
+                    encrypt(text, shift).into_bytes()
+                );
+            }
+        }
+    }
+    #[test]
+    fn side_by_side_pairs() {
+        assert_eq!(side_by_side("Hi", 1), "H→I\ni→j\n");
+        assert_eq!(side_by_side("中", 1), "中→中\n");
+    }
+    #[test]
+    fn encrypt_prefix_edges() {
+        assert_eq!(encrypt_prefix("Hello World", 3, 5), "Khoor World");
+        assert_eq!(encrypt_prefix("Hello", 3, 0), "Hello");
+        assert_eq!(encrypt_prefix("Hello", 3, 100), "Khoor");
+        assert_eq!(encrypt_prefix("éabc", 1, 2), "ébbc");
+    }
+    #[test]
+    fn encrypt_writer_across_chunks() {
+        use std::io::Write;
+
+        // the split falls inside the two bytes of 'é'
+        let text = "Hello, caf\u{e9}!".as_bytes();
+        let mut writer = EncryptWriter::new(Vec::new(), 3);
+        writer.write_all(&text[..11]).unwrap();
+        writer.write_all(&text[11..]).unwrap();
+        writer.flush().unwrap();
+        let out = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(out, "Khoor, fdi\u{e9}!");
+    }
+    #[test]
+    fn scored_candidates_sorted_by_score() {
+        let text = encrypt("Meet me by the old bridge after dark", 11);
+        let candidates = scored_candidates(&text);
+        assert_eq!(candidates.len(), 26);
+        assert_eq!(candidates[0].0, 11);
+        assert_eq!(candidates[0].1, decrypt(&text, 11));
+        assert!(candidates.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+        assert_eq!(candidates[0].2, chi_squared(&candidates[0].1));
+    }
+    #[test]
+    fn crack_with_confidence_levels() {
+        let plain = "It was the best of times, it was the worst of times, it was the age \
+                     of wisdom, it was the age of foolishness, it was the epoch of belief";
+        let (shift, text, confidence) = crack_with_confidence(&encrypt(plain, 9));
+        assert_eq!((shift, text.as_str()), (9, plain));
+        assert!(confidence > 0.5, "{}", confidence);
+
+        let (_, _, confidence) = crack_with_confidence("Ab");
+        assert!(confidence < 0.2, "{}", confidence);
+        assert_eq!(crack_with_confidence("123"), (0, "123".to_string(), 0.0));
+    }
+    #[test]
+    fn caesar_encrypt_const() {
+        const HIDDEN: &str = caesar_cipher_enc_dec::caesar_encrypt_const!("Hello, caf\u{e9}!", 3);
+        const BACK: &str = caesar_cipher_enc_dec::caesar_encrypt_const!("Abc", -1);
+        assert_eq!(HIDDEN, encrypt("Hello, caf\u{e9}!", 3));
+        assert_eq!(BACK, encrypt("Abc", -1));
+    }
+    #[test]
+    fn encrypt_default_uses_default_shift() {
+        assert_eq!(
+            encrypt_default("Hello, World!"),
+            encrypt("Hello, World!", DEFAULT_SHIFT)
+        );
+    }
+    #[test]
+    fn ensure_ascii_names_char() {
+        assert_eq!(ensure_ascii("Hello, World! 123"), Ok(()));
+        assert_eq!(
+            ensure_ascii("caf\u{e9} au lait"),
+            Err(CipherError::NonAscii('\u{e9}'))
+        );
+        assert_eq!(
+            CipherError::NonAscii('\u{e9}').to_string(),
+            "non-ASCII character '\u{e9}' in the text"
+        );
+    }
+    #[test]
+    fn encrypt_lines_keeps_empty_lines() {
+        let lines = vec!["Hello", "", "  World!", "123"];
+        assert_eq!(encrypt_lines(&lines, 3), ["Khoor", "", "  Zruog!", "123"]);
+        assert!(encrypt_lines(&[], 3).is_empty());
+    }
+    #[test]
+    fn best_shift_multi_combines_samples() {
+        let samples: Vec<String> = ["Cross river", "Flank left", "Lights off"]
+            .iter()
+            .map(|sample| encrypt(sample, 7))
+            .collect();
+        let samples: Vec<&str> = samples.iter().map(String::as_str).collect();
+        // each sample is too short to crack on its own
+        for sample in &samples {
+            assert_ne!(best_shift(sample), 7);
+        }
+        assert_eq!(best_shift_multi(&samples), 7);
+        assert_eq!(best_shift_multi(&[]), 0);
+    }
 }